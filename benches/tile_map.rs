@@ -0,0 +1,99 @@
+// run with `cargo bench`, prints how long the tile map heavy parts of a turn take on a
+// 200x200 level where every free tile holds a PUSH rock.
+use std::time::{Duration, Instant};
+use bevy_is_you::{block_registry::BlockRegistry, logic_types::*, level_data::*, simulation::*};
+
+const SIZE: usize = 200;
+
+fn registry() -> BlockRegistry {
+    let registry_json = serde_json::from_str(include_str!("../assets/blocks.registry.json")).unwrap();
+    BlockRegistry::from_json(&registry_json).unwrap()
+}
+
+fn push_level(registry: &BlockRegistry) -> LevelData {
+    let mut level = LevelData {
+        width: SIZE,
+        height: SIZE,
+        ..Default::default()
+    };
+
+    let bevy = registry.noun("bevy").unwrap();
+    let rock = registry.noun("rock").unwrap();
+
+    let word_tile = |text_block: TextBlock| registry.words.iter()
+        .find(|word| word.text_block == text_block)
+        .unwrap()
+        .tile_id;
+
+    let top = SIZE as i32 - 1;
+    let rules = [
+        [TextBlock::Noun(bevy), TextBlock::Is, TextBlock::You],
+        [TextBlock::Noun(rock), TextBlock::Is, TextBlock::Push],
+    ];
+
+    for (row, rule) in rules.iter().enumerate() {
+        for (column, text_block) in rule.iter().enumerate() {
+            let pos = GridPos::new(column as i32, top - row as i32);
+            level.text_blocks.tiles.push(LevelTile { tile_id: word_tile(*text_block), index: 0, pos });
+        }
+    }
+
+    let mut push_block = |noun: Noun, pos: GridPos| {
+        level.blocks.tiles.push(LevelTile { tile_id: registry.object(noun).tile_id, index: 0, pos });
+    };
+
+    push_block(bevy, GridPos::new(0, 0));
+
+    // the last column is left empty so every row can be pushed along.
+    for y in 0..top - 1 {
+        for x in 0..top {
+            if x == 0 && y == 0 { continue; }
+
+            push_block(rock, GridPos::new(x, y));
+        }
+    }
+
+    level
+}
+
+fn bench(name: &str, iterations: u32, mut run: impl FnMut()) {
+    let start = Instant::now();
+
+    for _ in 0..iterations {
+        run();
+    }
+
+    let elapsed = start.elapsed();
+    println!("{:<40} {:>12?} per iteration ({} iterations)", name, elapsed / iterations, iterations);
+}
+
+fn main() {
+    let registry = registry();
+    let level = push_level(&registry);
+    println!("{} objects on a {}x{} level", level.blocks.tiles.len() + level.text_blocks.tiles.len(), SIZE, SIZE);
+
+    bench("build simulation", 10, || {
+        let _ = Simulation::from_level(&level, &registry);
+    });
+
+    let mut simulation = Simulation::from_level(&level, &registry);
+
+    bench("tile lookup", 1_000_000, || {
+        let pos = GridPos::new(SIZE as i32 / 2, SIZE as i32 / 2);
+        std::hint::black_box(simulation.occupants(std::hint::black_box(pos)).count());
+    });
+
+    // pushes the whole bottom row of 198 rocks one tile, undoing it outside of the timed part.
+    let mut turns = Duration::ZERO;
+    let iterations = 100;
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        simulation.step(BlockDirection::Right);
+        turns += start.elapsed();
+
+        simulation.undo();
+    }
+
+    println!("{:<40} {:>12?} per iteration ({} iterations)", "push a row of rocks", turns / iterations, iterations);
+}
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use anyhow::{bail, Context};
+use crate::{logic_types::*, level_data::{LevelData, LevelLayer, LevelParseWarning}};
+
+pub const BLOCK_REGISTRY_PATH: &str = "blocks.registry.json";
+
+#[derive(Clone, Debug)]
+pub struct ObjectDefinition {
+    pub name: String,
+    pub sprite: String,
+    // the tile id in the "blocks" layer of the Ogmo project
+    pub tile_id: i64,
+    pub layer: f32,
+    // the level opened by walking onto the object in the main area
+    pub level: Option<usize>,
+    // sprites for the ways the object can face, shown instead of `sprite`
+    pub facing_sprites: Vec<(Facing, String)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct WordDefinition {
+    pub text_block: TextBlock,
+    pub sprite: String,
+    // the tile id in the "text blocks" layer of the Ogmo project
+    pub tile_id: i64,
+}
+
+// Every object and word levels can be made of, read from `assets/blocks.registry.json`.
+// An object with a "noun" entry gets a text tile naming it, so new objects work in rules without touching any code.
+#[cfg_attr(feature = "bevy", derive(bevy::reflect::TypeUuid), uuid = "0d3b1e8c-92a4-4e61-b7f5-6a2c8d4e9f10")]
+#[derive(Clone, Debug, Default)]
+pub struct BlockRegistry {
+    pub objects: Vec<ObjectDefinition>,
+    pub words: Vec<WordDefinition>,
+    object_tiles: HashMap<i64, Noun>,
+    word_tiles: HashMap<i64, usize>,
+}
+
+impl BlockRegistry {
+    pub fn from_json(value: &serde_json::Value) -> Result<Self, anyhow::Error> {
+        let mut registry = BlockRegistry::default();
+
+        let objects = match value["objects"].as_array() {
+            Some(objects) => objects,
+            None => bail!("expected an \"objects\" array"),
+        };
+
+        for (index, object) in objects.iter().enumerate() {
+            let name = match object["name"].as_str() {
+                Some(name) => name.to_string(),
+                None => bail!("object {} has no \"name\"", index),
+            };
+
+            let noun = Noun(index as u16);
+            let tile_id = required_tile(object, &name)?;
+            let sprite = required_sprite(object, &name)?;
+
+            if registry.object_tiles.insert(tile_id, noun).is_some() {
+                bail!("\"{}\" uses tile {} which is already taken by another object", name, tile_id);
+            }
+
+            if !object["noun"].is_null() {
+                registry.push_word(TextBlock::Noun(noun), &object["noun"], &name)?;
+            }
+
+            registry.objects.push(ObjectDefinition {
+                tile_id,
+                sprite,
+                layer: object["layer"].as_f64().unwrap_or(0.03) as f32,
+                level: object["level"].as_u64().map(|level| level as usize),
+                facing_sprites: facing_sprites(object, &name)?,
+                name,
+            });
+        }
+
+        let words = match value["words"].as_array() {
+            Some(words) => words,
+            None => bail!("expected a \"words\" array"),
+        };
+
+        for (index, word) in words.iter().enumerate() {
+            let name = match word["word"].as_str() {
+                Some(name) => name,
+                None => bail!("word {} has no \"word\"", index),
+            };
+
+            let text_block = match TextBlock::from_word(name) {
+                Some(text_block) => text_block,
+                None => bail!("\"{}\" isn't a word the game knows, only nouns can be added without code", name),
+            };
+
+            registry.push_word(text_block, word, name)?;
+        }
+
+        Ok(registry)
+    }
+
+    fn push_word(&mut self, text_block: TextBlock, value: &serde_json::Value, name: &str) -> Result<(), anyhow::Error> {
+        let tile_id = required_tile(value, name)?;
+
+        if self.word_tiles.insert(tile_id, self.words.len()).is_some() {
+            bail!("the word \"{}\" uses tile {} which is already taken by another word", name, tile_id);
+        }
+
+        self.words.push(WordDefinition {
+            text_block,
+            sprite: required_sprite(value, name)?,
+            tile_id,
+        });
+
+        Ok(())
+    }
+
+    pub fn noun(&self, name: &str) -> Option<Noun> {
+        self.objects.iter()
+            .position(|object| object.name == name)
+            .map(|index| Noun(index as u16))
+    }
+
+    #[inline]
+    pub fn object(&self, noun: Noun) -> &ObjectDefinition {
+        &self.objects[noun.0 as usize]
+    }
+
+    pub fn block_from_tile(&self, tile_id: i64) -> Option<Block> {
+        self.object_tiles.get(&tile_id).map(|noun| Block::Object(*noun))
+    }
+
+    pub fn text_block_from_tile(&self, tile_id: i64) -> Option<TextBlock> {
+        self.word_tiles.get(&tile_id).map(|index| self.words[*index].text_block)
+    }
+
+    pub fn layer_of(&self, block: Block) -> f32 {
+        match block {
+            Block::Object(noun) => self.object(noun).layer,
+            _ => 0.02,
+        }
+    }
+
+    // reports the tiles of a level that no object or word in the registry uses.
+    pub fn unknown_tiles(&self, level: &LevelData) -> Vec<LevelParseWarning> {
+        let mut warnings = Vec::new();
+
+        let mut check_layer = |layer: &LevelLayer, is_known: &dyn Fn(i64) -> bool| {
+            for tile in &layer.tiles {
+                if is_known(tile.tile_id) { continue; }
+
+                warnings.push(LevelParseWarning::UnknownTile {
+                    layer: layer.name.clone(),
+                    index: tile.index,
+                    tile_id: tile.tile_id,
+                });
+            }
+        };
+
+        check_layer(&level.blocks, &|tile_id| self.block_from_tile(tile_id).is_some());
+        check_layer(&level.text_blocks, &|tile_id| self.text_block_from_tile(tile_id).is_some());
+
+        warnings
+    }
+}
+
+fn required_tile(value: &serde_json::Value, name: &str) -> Result<i64, anyhow::Error> {
+    value["tile"].as_i64().with_context(|| format!("\"{}\" has no \"tile\" id", name))
+}
+
+fn required_sprite(value: &serde_json::Value, name: &str) -> Result<String, anyhow::Error> {
+    value["sprite"].as_str()
+        .map(|sprite| sprite.to_string())
+        .with_context(|| format!("\"{}\" has no \"sprite\"", name))
+}
+
+fn facing_sprites(value: &serde_json::Value, name: &str) -> Result<Vec<(Facing, String)>, anyhow::Error> {
+    let sprites = match value["facing"].as_object() {
+        Some(sprites) => sprites,
+        None if value["facing"].is_null() => return Ok(Vec::new()),
+        None => bail!("\"{}\" has a \"facing\" entry that isn't an object", name),
+    };
+
+    let mut facing_sprites = Vec::new();
+
+    for (facing_name, sprite) in sprites {
+        let facing = match Facing::from_name(facing_name) {
+            Some(facing) => facing,
+            None => bail!("\"{}\" has a sprite for \"{}\", which isn't up, down, left or right", name, facing_name),
+        };
+
+        match sprite.as_str() {
+            Some(sprite) => facing_sprites.push((facing, sprite.to_string())),
+            None => bail!("\"{}\" has a \"{}\" sprite that isn't a path", name, facing_name),
+        }
+    }
+
+    Ok(facing_sprites)
+}
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{AppState, game_logic::*};
+use crate::{AppState, game_logic::*, turn_input::clear_turn_input};
 
 pub struct GameLogicPlugin;
 
@@ -10,19 +10,15 @@ impl Plugin for GameLogicPlugin {
             .add_system_set(
                 SystemSet::on_enter(AppState::Game)
                     .with_system(setup_world)  
-                     
+                    .with_system(clear_turn_input)
             )
             .add_system_set(
                 SystemSet::on_update(AppState::Game)
                     .with_system(apply_mover)
-                    .with_system(change_block_texture)
-                    .with_system(apply_constraints.before(apply_mover))
-                    .with_system(map_tiles.after(apply_mover))
-                    .with_system(record_world.after(apply_attributes).before(apply_queue))
-                    .with_system(apply_attributes.after(map_tiles))
-                    .with_system(apply_queue.after(apply_attributes))
-                    .with_system(evaluate_text.after(apply_attributes).before(apply_queue))
-                    .with_system(apply_record_to_world.after(apply_attributes).before(apply_queue).after(record_world))
+                    .with_system(take_turn.after(apply_mover))
+                    .with_system(sync_world.after(take_turn))
+                    .with_system(apply_grid_pos.after(sync_world))
+                    .with_system(change_block_texture.after(sync_world))
                     .with_system(check_if_win)
             )
             .add_system_set(
@@ -0,0 +1,155 @@
+use std::fmt;
+use crate::logic_types::*;
+
+// A level as read from an Ogmo json export, before the `BlockRegistry` turns its tiles into objects.
+#[cfg_attr(feature = "bevy", derive(bevy::reflect::TypeUuid), uuid = "5c4a3a53-4f0e-4a8d-9a2b-0e7c1a64d1f3")]
+#[derive(Default)]
+pub struct LevelData {
+    // both measured in tiles
+    pub width: usize,
+    pub height: usize,
+    pub blocks: LevelLayer,
+    pub text_blocks: LevelLayer,
+    pub warnings: Vec<LevelParseWarning>,
+}
+
+// a non empty tile of a layer, what it turns into is up to the `BlockRegistry`.
+#[derive(Clone, Copy, Debug)]
+pub struct LevelTile {
+    pub tile_id: i64,
+    // index into the layer's "data" array
+    pub index: usize,
+    pub pos: GridPos,
+}
+
+#[derive(Default, Debug)]
+pub struct LevelLayer {
+    pub name: String,
+    pub tiles: Vec<LevelTile>,
+}
+
+#[derive(Debug)]
+pub enum LevelParseError {
+    MissingField(&'static str),
+    MissingLayer(usize),
+    MissingData { layer: String },
+    InvalidTile { layer: String, index: usize, value: serde_json::Value },
+}
+
+impl fmt::Display for LevelParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelParseError::MissingField(field) => write!(f, "missing or invalid \"{}\"", field),
+            LevelParseError::MissingLayer(index) => write!(f, "missing layer {}", index),
+            LevelParseError::MissingData { layer } => write!(f, "layer \"{}\" has no \"data\" array", layer),
+            LevelParseError::InvalidTile { layer, index, value } => {
+                write!(f, "layer \"{}\", cell {}: expected a tile id but found {}", layer, index, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LevelParseError {}
+
+// problems that don't stop the level from being played but probably aren't what the designer meant.
+#[derive(Debug)]
+pub enum LevelParseWarning {
+    UnknownTile { layer: String, index: usize, tile_id: i64 },
+    DataLengthMismatch { layer: String, cells_x: usize, cells_y: usize, data_len: usize },
+    LayerSizeMismatch { layer: String, cells_x: usize, cells_y: usize, width: usize, height: usize },
+    NonStandardGrid { layer: String, cell_width: u64, cell_height: u64 },
+}
+
+impl fmt::Display for LevelParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelParseWarning::UnknownTile { layer, index, tile_id } => {
+                write!(f, "layer \"{}\", cell {}: unknown tile id {}, it was skipped", layer, index, tile_id)
+            }
+
+            LevelParseWarning::DataLengthMismatch { layer, cells_x, cells_y, data_len } => {
+                write!(f, "layer \"{}\" is {}x{} tiles but has {} cells of data", layer, cells_x, cells_y, data_len)
+            }
+
+            LevelParseWarning::LayerSizeMismatch { layer, cells_x, cells_y, width, height } => {
+                write!(f, "layer \"{}\" is {}x{} tiles but the level is {}x{}, tiles outside of it were skipped", layer, cells_x, cells_y, width, height)
+            }
+
+            LevelParseWarning::NonStandardGrid { layer, cell_width, cell_height } => {
+                write!(f, "layer \"{}\" uses a {}x{} grid, tiles are expected to be 16x16", layer, cell_width, cell_height)
+            }
+        }
+    }
+}
+
+pub fn create_level_data(value: serde_json::Value) -> Result<LevelData, LevelParseError> {
+    let mut level_data = LevelData {
+        width: value["width"].as_u64().ok_or(LevelParseError::MissingField("width"))? as usize / 16,
+        height: value["height"].as_u64().ok_or(LevelParseError::MissingField("height"))? as usize / 16,
+        ..Default::default()
+    };
+
+    let layers = value["layers"].as_array().ok_or(LevelParseError::MissingField("layers"))?;
+
+    level_data.blocks = parse_layer(&mut level_data, layers, 0)?;
+    level_data.text_blocks = parse_layer(&mut level_data, layers, 1)?;
+
+    Ok(level_data)
+}
+
+fn layer_name(layer: &serde_json::Value, index: usize) -> String {
+    match layer["name"].as_str() {
+        Some(name) => name.to_string(),
+        None => format!("#{}", index),
+    }
+}
+
+// Ogmo lays the data out row by row starting from the top left, where our rows count up from the bottom.
+fn parse_layer(level_data: &mut LevelData, layers: &[serde_json::Value], layer_index: usize) -> Result<LevelLayer, LevelParseError> {
+    let layer = layers.get(layer_index).ok_or(LevelParseError::MissingLayer(layer_index))?;
+    let name = layer_name(layer, layer_index);
+
+    let data = match layer["data"].as_array() {
+        Some(data) => data,
+        None => return Err(LevelParseError::MissingData { layer: name }),
+    };
+
+    let cell_width = layer["gridCellWidth"].as_u64().unwrap_or(16);
+    let cell_height = layer["gridCellHeight"].as_u64().unwrap_or(16);
+
+    if cell_width != 16 || cell_height != 16 {
+        level_data.warnings.push(LevelParseWarning::NonStandardGrid { layer: name.clone(), cell_width, cell_height });
+    }
+
+    let cells_x = layer["gridCellsX"].as_u64().map_or(level_data.width, |cells_x| cells_x as usize);
+    let cells_y = layer["gridCellsY"].as_u64().map_or(level_data.height, |cells_y| cells_y as usize);
+
+    if cells_x != level_data.width || cells_y != level_data.height {
+        let (width, height) = (level_data.width, level_data.height);
+        level_data.warnings.push(LevelParseWarning::LayerSizeMismatch { layer: name.clone(), cells_x, cells_y, width, height });
+    }
+
+    if cells_x * cells_y != data.len() {
+        level_data.warnings.push(LevelParseWarning::DataLengthMismatch { layer: name.clone(), cells_x, cells_y, data_len: data.len() });
+    }
+
+    let mut tiles = Vec::new();
+
+    for (index, value) in data.iter().enumerate() {
+        let tile_id = match value.as_i64() {
+            Some(tile_id) => tile_id,
+            None => return Err(LevelParseError::InvalidTile { layer: name, index, value: value.clone() }),
+        };
+
+        if tile_id == -1 || cells_x == 0 { continue; }
+
+        let pos = GridPos::new((index % cells_x) as i32, level_data.height as i32 - 1 - (index / cells_x) as i32);
+
+        // anything past the edges has already been reported as a layer or data size mismatch.
+        if pos.x >= level_data.width as i32 || pos.y < 0 { continue; }
+
+        tiles.push(LevelTile { tile_id, index, pos });
+    }
+
+    Ok(LevelLayer { name, tiles })
+}
@@ -0,0 +1,28 @@
+pub mod block_registry;
+pub mod level_data;
+pub mod logic_types;
+pub mod rules;
+pub mod simulation;
+
+// the game itself, everything above can be built and tested without bevy using `--no-default-features`.
+#[cfg(feature = "bevy")]
+pub mod game_logic;
+#[cfg(feature = "bevy")]
+pub mod game_logic_types;
+#[cfg(feature = "bevy")]
+pub mod game_logic_plugin;
+#[cfg(feature = "bevy")]
+pub mod main_area_logic_plugin;
+#[cfg(feature = "bevy")]
+pub mod loading_levels;
+#[cfg(feature = "bevy")]
+pub mod turn_input;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
+pub enum AppState {
+    Loading,
+    MainArea,
+    Game,
+}
+
+pub struct LevelIndex(pub usize);
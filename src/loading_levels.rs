@@ -1,130 +1,243 @@
+use std::path::Path;
+use anyhow::Context;
+use bevy::{prelude::*, asset::{AssetLoader, AssetPath, LoadContext, LoadState, LoadedAsset}, reflect::TypeUuid, utils::BoxedFuture};
+use crate::{block_registry::*, level_data::*, AppState, LevelIndex};
 
-use bevy::prelude::*;
-use crate::game_logic_types::*;
+// the levels listed by the manifest in the order they're indexed by `LevelIndex`,
+// index 0 being the main area.
+#[derive(TypeUuid)]
+#[uuid = "b1f0e6a2-7d43-4c55-8f3e-2a9d6c1e0b47"]
+pub struct LevelManifest(pub Vec<Handle<LevelData>>);
 
-#[macro_export]
-macro_rules! load_levels {
-    ($($level_name: expr),*) => {{
-        let mut levels = Vec::<LevelData>::new();
+#[derive(Default)]
+pub struct Levels {
+    pub manifest: Handle<LevelManifest>,
+    // what the tiles of every level turn into
+    pub registry: Handle<BlockRegistry>,
+    pub levels: Vec<Handle<LevelData>>,
+}
 
-        $(
-            {
-                let level_string_data = include_str!(concat!("../assets/maps/", $level_name, ".json"));
-                let level_json: serde_json::Value = match serde_json::from_str(level_string_data) {
-                    Ok(value) => value,
-                    Err(err) => {
-                        panic!("Failed to load \"{}\": {}", $level_name, err);
-                    }
-                };
+impl Levels {
+    pub fn get<'a>(&self, level_assets: &'a Assets<LevelData>, index: usize) -> Option<&'a LevelData> {
+        self.levels.get(index).and_then(|handle| level_assets.get(handle))
+    }
+}
+
+pub const LEVEL_MANIFEST_PATH: &str = "levels.manifest.json";
+
+// loads an Ogmo level exported as json.
+#[derive(Default)]
+pub struct LevelLoader;
 
-                let level_data = create_level_data(level_json);
+impl AssetLoader for LevelLoader {
+    fn load<'a>(&'a self, bytes: &'a [u8], load_context: &'a mut LoadContext) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let path = load_context.path().display().to_string();
+            let level_json: serde_json::Value = serde_json::from_slice(bytes)
+                .with_context(|| format!("\"{}\" isn't valid json", path))?;
 
-                levels.push(level_data);
+            let level_data = create_level_data(level_json)
+                .with_context(|| format!("\"{}\" isn't a valid level", path))?;
+
+            for warning in &level_data.warnings {
+                warn!("\"{}\": {}", path, warning);
             }
-        )*
 
-        Levels(levels)
-    }};
+            load_context.set_default_asset(LoadedAsset::new(level_data));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
 }
 
+// loads a list of level paths, relative to the manifest, and every level in it alongside.
 #[derive(Default)]
-pub struct LevelData {
-    pub width: usize,
-    pub height: usize,
-    pub blocks: Vec<(Block, Vec2)>,
-    pub text_blocks: Vec<(TextBlock, Vec2)>,
+pub struct LevelManifestLoader;
+
+impl AssetLoader for LevelManifestLoader {
+    fn load<'a>(&'a self, bytes: &'a [u8], load_context: &'a mut LoadContext) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let manifest_json: serde_json::Value = serde_json::from_slice(bytes)?;
+            let directory = load_context.path().parent().unwrap_or_else(|| Path::new(""));
+
+            let level_names = match manifest_json["levels"].as_array() {
+                Some(level_names) => level_names,
+                None => anyhow::bail!("expected a \"levels\" array of level paths"),
+            };
+
+            let mut level_paths = Vec::new();
+
+            for level_name in level_names {
+                match level_name.as_str() {
+                    Some(level_name) => level_paths.push(AssetPath::new(directory.join(level_name), None)),
+                    None => anyhow::bail!("expected a level path but found {}", level_name),
+                }
+            }
+
+            let levels = level_paths.iter()
+                .map(|level_path| load_context.get_handle(level_path.clone()))
+                .collect();
+
+            load_context.set_default_asset(LoadedAsset::new(LevelManifest(levels)).with_dependencies(level_paths));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["manifest.json"]
+    }
 }
 
-pub struct Levels(pub Vec<LevelData>);
+#[derive(Default)]
+pub struct BlockRegistryLoader;
+
+impl AssetLoader for BlockRegistryLoader {
+    fn load<'a>(&'a self, bytes: &'a [u8], load_context: &'a mut LoadContext) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let registry_json: serde_json::Value = serde_json::from_slice(bytes)?;
+            let registry = BlockRegistry::from_json(&registry_json)
+                .with_context(|| format!("\"{}\" isn't a valid block registry", load_context.path().display()))?;
+
+            load_context.set_default_asset(LoadedAsset::new(registry));
+            Ok(())
+        })
+    }
 
-pub fn create_level_data(value: serde_json::Value) -> LevelData {
-    let mut level_data: LevelData = LevelData {
-        width: 0,
-        height: 0,
-        blocks: Vec::new(),
-        text_blocks: Vec::new(),
+    fn extensions(&self) -> &[&str] {
+        &["registry.json"]
+    }
+}
+
+pub fn start_loading_levels(asset_server: Res<AssetServer>, mut levels: ResMut<Levels>) {
+    levels.manifest = asset_server.load(LEVEL_MANIFEST_PATH);
+    levels.registry = asset_server.load(BLOCK_REGISTRY_PATH);
+}
+
+// waits for the block registry, the manifest and every level in it, reporting the ones that couldn't be loaded.
+#[allow(clippy::too_many_arguments)]
+pub fn check_levels_loaded(
+    asset_server: Res<AssetServer>,
+    manifests: Res<Assets<LevelManifest>>,
+    registries: Res<Assets<BlockRegistry>>,
+    level_assets: Res<Assets<LevelData>>,
+    mut levels: ResMut<Levels>,
+    mut block_registry: ResMut<BlockRegistry>,
+    mut app_state: ResMut<State<AppState>>,
+    mut reported: Local<bool>,
+) {
+    if *reported { return; }
+
+    let registry = match asset_server.get_load_state(&levels.registry) {
+        LoadState::Loaded => registries.get(&levels.registry).unwrap(),
+        LoadState::Failed => {
+            error!("Failed to load the block registry \"{}\"", BLOCK_REGISTRY_PATH);
+            *reported = true;
+            return;
+        }
+
+        _ => return,
     };
 
-    level_data.width = value["width"].as_u64().unwrap() as usize;
-    level_data.height = value["height"].as_u64().unwrap() as usize;
-    
-    let layer = value["layers"].as_array().unwrap();
-    
-    let width = (level_data.width / 16) as i32;
-    let height = (level_data.height / 16) as i32;
-    
-    let mut current_row: i32;
-    let mut current_column: i32;
-
-    macro_rules! init {
-        ($block_type: expr) => {{
-            level_data.blocks.push(($block_type, Vec2::new((current_row * 16) as f32, (current_column * 16) as f32)));
-        }};
-
-        (text, $text_block_type: expr) => {{
-            level_data.text_blocks.push(($text_block_type, Vec2::new((current_row * 16) as f32, (current_column * 16) as f32)));
-        }};
+    let manifest = match asset_server.get_load_state(&levels.manifest) {
+        LoadState::Loaded => manifests.get(&levels.manifest).unwrap(),
+        LoadState::Failed => {
+            error!("Failed to load the level manifest \"{}\"", LEVEL_MANIFEST_PATH);
+            *reported = true;
+            return;
+        }
+
+        _ => return,
+    };
+
+    let mut all_done = true;
+
+    for handle in &manifest.0 {
+        if matches!(asset_server.get_load_state(handle), LoadState::NotLoaded | LoadState::Loading) {
+            all_done = false;
+        }
     }
 
-    macro_rules! data_loop {
-        ($index: expr, { $($pat: pat => $result: expr),* }) => {
-            current_row = 0;
-            current_column = height-1;
-
-            for data in layer[$index]["data"].as_array().unwrap() {
-                match data.as_i64().unwrap() {
-                    $(
-                        $pat => {
-                            $result
-                        }
-                    ),*
+    if !all_done { return; }
+
+    for (index, handle) in manifest.0.iter().enumerate() {
+        let path = asset_server.get_handle_path(handle).map(|path| path.path().to_owned());
+
+        match level_assets.get(handle) {
+            Some(level) => {
+                for warning in registry.unknown_tiles(level) {
+                    warn!("{:?}: {}", path, warning);
                 }
+            }
 
-                current_row += 1;
+            None => error!("Failed to load level {} ({:?}), it won't be playable", index, path),
+        }
+    }
+
+    *block_registry = registry.clone();
+    levels.levels = manifest.0.clone();
+    *reported = true;
 
-                if current_row >= width {
-                    current_column -= 1;
-                    current_row = 0;
+    let main_area = match levels.levels.first() {
+        Some(main_area) => main_area,
+        None => {
+            error!("\"{}\" doesn't list any levels, there is nothing to play", LEVEL_MANIFEST_PATH);
+            return;
+        }
+    };
+
+    if asset_server.get_load_state(main_area) != LoadState::Loaded {
+        error!("The main area failed to load, there is nothing to play");
+        return;
+    }
+
+    let _ = app_state.set(AppState::MainArea);
+}
+
+// rebuilds the level being played whenever its file changes on disk, `LevelIndex` is left as is
+// so the designer stays on the level they're editing.
+#[allow(clippy::too_many_arguments)]
+pub fn reload_changed_levels(
+    mut level_events: EventReader<AssetEvent<LevelData>>,
+    mut manifest_events: EventReader<AssetEvent<LevelManifest>>,
+    manifests: Res<Assets<LevelManifest>>,
+    level_assets: Res<Assets<LevelData>>,
+    block_registry: Res<BlockRegistry>,
+    mut levels: ResMut<Levels>,
+    level_index: Res<LevelIndex>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    let playing = *app_state.current() != AppState::Loading;
+
+    for event in manifest_events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            if !playing || *handle != levels.manifest { continue; }
+
+            if let Some(manifest) = manifests.get(handle) {
+                info!("Level manifest changed, {} levels are now listed", manifest.0.len());
+                levels.levels = manifest.0.clone();
+            }
+        }
+    }
+
+    let mut current_level_changed = false;
+
+    for event in level_events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            if let Some(level) = level_assets.get(handle) {
+                for warning in block_registry.unknown_tiles(level) {
+                    warn!("{}", warning);
                 }
             }
-        };
+
+            current_level_changed |= levels.levels.get(level_index.0) == Some(handle);
+        }
     }
 
-    data_loop!(0, {
-        0 => init!(Block::Bevy),
-        1 => init!(Block::Wall),
-        2 => init!(Block::Rock),
-        3 => init!(Block::Flag),
-        4 => init!(Block::Tree),
-        5 => init!(Block::Level01),
-        6 => init!(Block::Level02),
-        7 => init!(Block::Level03),
-        8 => init!(Block::Level04),
-        9 => init!(Block::Level05),
-        10 => init!(Block::Level06),
-        11 => init!(Block::Level07),
-        12 => init!(Block::Level08),
-        13 => init!(Block::Level09),
-        14 => init!(Block::Path),
-        15 => init!(Block::Water),
-        _ => {}
-    });
-
-    data_loop!(1, {
-        0 => init!(text, TextBlock::Is),
-        1 => init!(text, TextBlock::Bevy),
-        2 => init!(text, TextBlock::You),
-        3 => init!(text, TextBlock::Stop),
-        4 => init!(text, TextBlock::Push),
-        5 => init!(text, TextBlock::Wall),
-        6 => init!(text, TextBlock::Rock),
-        7 => init!(text, TextBlock::Flag),
-        8 => init!(text, TextBlock::Win),
-        9 => init!(text, TextBlock::Sink),
-        10 => init!(text, TextBlock::Tree),
-        11 => init!(text, TextBlock::Water),
-        _ => {}
-    });
-
-    level_data
-}
\ No newline at end of file
+    if playing && current_level_changed {
+        info!("Level {} changed on disk, reloading it", level_index.0);
+        let _ = app_state.restart();
+    }
+}
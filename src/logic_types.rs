@@ -0,0 +1,247 @@
+// Everything the rules and the simulation are made of. Nothing here depends on bevy, the types that are also
+// components only derive `Component` when the game is built with the "bevy" feature.
+
+// the tile an object is on, (0, 0) being the bottom left of the level.
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Component))]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub struct GridPos {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl GridPos {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn offset(&self, direction: BlockDirection) -> Self {
+        match direction {
+            BlockDirection::Right => Self::new(self.x + 1, self.y),
+            BlockDirection::Left  => Self::new(self.x - 1, self.y),
+            BlockDirection::Up    => Self::new(self.x, self.y + 1),
+            BlockDirection::Down  => Self::new(self.x, self.y - 1),
+            BlockDirection::None  => *self,
+        }
+    }
+}
+
+// an object type from the block registry, indexing `BlockRegistry::objects`.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, PartialOrd, Ord)]
+pub struct Noun(pub u16);
+
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Component))]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Block {
+    Object(Noun),
+    Text,
+}
+
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Component))]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum TextBlock {
+    Noun(Noun),
+    Is,
+    You,
+    Push,
+    Stop,
+    Win,
+    Sink,
+    And,
+    Not,
+    On,
+    Near,
+    Facing,
+    Lonely,
+    Has,
+    Make,
+    // TEXT, the noun for every text block
+    TextNoun,
+    Move,
+    Pull,
+    Defeat,
+    Hot,
+    Melt,
+    Open,
+    Shut,
+    Float,
+    Shift,
+    Tele,
+    Swap,
+    Weak,
+    More,
+    // UP, DOWN, LEFT and RIGHT
+    Direction(Facing),
+    Fall,
+    Tint(Tint),
+    Hide,
+}
+
+impl TextBlock {
+    // the words a registry can place in levels besides nouns, each one needs code to do anything.
+    pub fn from_word(word: &str) -> Option<TextBlock> {
+        match word {
+            "is" => Some(TextBlock::Is),
+            "you" => Some(TextBlock::You),
+            "push" => Some(TextBlock::Push),
+            "stop" => Some(TextBlock::Stop),
+            "win" => Some(TextBlock::Win),
+            "sink" => Some(TextBlock::Sink),
+            "and" => Some(TextBlock::And),
+            "not" => Some(TextBlock::Not),
+            "on" => Some(TextBlock::On),
+            "near" => Some(TextBlock::Near),
+            "facing" => Some(TextBlock::Facing),
+            "lonely" => Some(TextBlock::Lonely),
+            "has" => Some(TextBlock::Has),
+            "make" => Some(TextBlock::Make),
+            "text" => Some(TextBlock::TextNoun),
+            "move" => Some(TextBlock::Move),
+            "pull" => Some(TextBlock::Pull),
+            "defeat" => Some(TextBlock::Defeat),
+            "hot" => Some(TextBlock::Hot),
+            "melt" => Some(TextBlock::Melt),
+            "open" => Some(TextBlock::Open),
+            "shut" => Some(TextBlock::Shut),
+            "float" => Some(TextBlock::Float),
+            "shift" => Some(TextBlock::Shift),
+            "tele" => Some(TextBlock::Tele),
+            "swap" => Some(TextBlock::Swap),
+            "weak" => Some(TextBlock::Weak),
+            "more" => Some(TextBlock::More),
+            "fall" => Some(TextBlock::Fall),
+            "red" => Some(TextBlock::Tint(Tint::Red)),
+            "blue" => Some(TextBlock::Tint(Tint::Blue)),
+            "green" => Some(TextBlock::Tint(Tint::Green)),
+            "hide" => Some(TextBlock::Hide),
+
+            _ => Facing::from_name(word).map(TextBlock::Direction),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlockDirection {
+    Right,
+    Left,
+    Up,
+    Down,
+    None,
+}
+
+impl BlockDirection {
+    pub fn reversed(self) -> BlockDirection {
+        match self {
+            BlockDirection::Right => BlockDirection::Left,
+            BlockDirection::Left => BlockDirection::Right,
+            BlockDirection::Up => BlockDirection::Down,
+            BlockDirection::Down => BlockDirection::Up,
+            BlockDirection::None => BlockDirection::None,
+        }
+    }
+}
+
+// the way an object looks, which is also where MOVE takes it. Objects face right until they first move.
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Component))]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+pub enum Facing {
+    #[default]
+    Right,
+    Left,
+    Up,
+    Down,
+}
+
+impl Facing {
+    pub fn from_direction(direction: BlockDirection) -> Option<Facing> {
+        match direction {
+            BlockDirection::Right => Some(Facing::Right),
+            BlockDirection::Left => Some(Facing::Left),
+            BlockDirection::Up => Some(Facing::Up),
+            BlockDirection::Down => Some(Facing::Down),
+            BlockDirection::None => None,
+        }
+    }
+
+    // the names used for directional sprites in the block registry.
+    pub fn from_name(name: &str) -> Option<Facing> {
+        match name {
+            "right" => Some(Facing::Right),
+            "left" => Some(Facing::Left),
+            "up" => Some(Facing::Up),
+            "down" => Some(Facing::Down),
+
+            _ => None,
+        }
+    }
+
+    pub fn direction(self) -> BlockDirection {
+        match self {
+            Facing::Right => BlockDirection::Right,
+            Facing::Left => BlockDirection::Left,
+            Facing::Up => BlockDirection::Up,
+            Facing::Down => BlockDirection::Down,
+        }
+    }
+
+    pub fn reversed(self) -> Facing {
+        match self {
+            Facing::Right => Facing::Left,
+            Facing::Left => Facing::Right,
+            Facing::Up => Facing::Down,
+            Facing::Down => Facing::Up,
+        }
+    }
+}
+
+// the colors RED, BLUE and GREEN paint objects in.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Tint {
+    Red,
+    Blue,
+    Green,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attribute {
+    You,
+    Stop,
+    Push,
+    Win,
+    Sink,
+    LevelSelect(usize),
+    // leaves the block behind when destroyed
+    Has(Block),
+    // puts the block on its tile at the end of every turn, unless one is already there
+    Make(Block),
+    // walks a tile the way it's facing every turn, turning around when it can't
+    Move,
+    // follows whatever moves away from it
+    Pull,
+    // destroys YOU objects on its tile
+    Defeat,
+    // destroys MELT objects on its tile
+    Hot,
+    Melt,
+    // an OPEN and a SHUT object on the same tile destroy each other
+    Open,
+    Shut,
+    // only touches other floating objects, and only those that don't float touch the ones that don't
+    Float,
+    // carries what's on its tile a tile the way it's facing every turn
+    Shift,
+    // sends what steps onto it to the next one of its kind
+    Tele,
+    // trades places with whatever moves onto it, or onto whatever it moves onto
+    Swap,
+    // breaks when it shares its tile with anything or a push into it is blocked
+    Weak,
+    // grows into the tiles around it every turn
+    More,
+    // turns it that way at the end of every turn
+    Face(Facing),
+    // drops it down as far as it goes once everything else has moved
+    Fall,
+    // RED, BLUE and GREEN, only change how it's drawn but can be checked by rules like RED ROCK IS PUSH
+    Tint(Tint),
+    Hide,
+}
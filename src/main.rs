@@ -6,23 +6,8 @@
     windows_subsystem = "windows",
 )]
 
-pub mod game_logic;
-pub mod game_logic_types;
-pub mod game_logic_plugin;
-pub mod main_area_logic_plugin;
-pub mod loading_levels;
-
-use bevy::{prelude::*};
-use main_area_logic_plugin::MainAreaPlugin;
-use crate::{game_logic_plugin::*};
-
-#[derive(PartialEq, Eq, Debug, Clone, Copy, Hash)]
-pub enum AppState {
-    MainArea,
-    Game,
-}
-
-pub struct LevelIndex(pub usize);
+use bevy::{prelude::*, asset::AssetServerSettings};
+use bevy_is_you::{AppState, LevelIndex, game_logic_plugin::*, main_area_logic_plugin::MainAreaPlugin};
 
 fn main() {
     App::new()
@@ -34,8 +19,13 @@ fn main() {
             ..default()
         }) 
         .insert_resource(LevelIndex(0))
+        // lets levels be edited while the game is running, see `reload_changed_levels`.
+        .insert_resource(AssetServerSettings {
+            watch_for_changes: !cfg!(target_arch = "wasm32"),
+            ..default()
+        })
         .add_plugins(DefaultPlugins)
-        .add_state(AppState::MainArea)
+        .add_state(AppState::Loading)
         .add_plugin(GameLogicPlugin)
         .add_plugin(MainAreaPlugin)
         .run();
@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use crate::{AppState, game_logic::*, LevelIndex, loading_levels::*, load_levels, game_logic_types::*};
+use crate::{AppState, game_logic::*, LevelIndex, level_data::LevelData, loading_levels::*, game_logic_types::*, block_registry::*, simulation::Simulation, turn_input::*};
 
 fn change_level_to_main(mut level_index: ResMut<LevelIndex>) {
     level_index.0 = 0;
@@ -10,30 +10,45 @@ pub struct MainAreaPlugin;
 impl Plugin for MainAreaPlugin {
     fn build(&self, app: &mut App) {
         app
-            .insert_resource(load_levels!("main map", "level1", "level2", "level3", "level4"))
+            .add_asset::<LevelData>()
+            .add_asset::<LevelManifest>()
+            .init_asset_loader::<LevelLoader>()
+            .init_asset_loader::<LevelManifestLoader>()
+            .add_asset::<BlockRegistry>()
+            .init_asset_loader::<BlockRegistryLoader>()
+            .init_resource::<BlockRegistry>()
+            .init_resource::<Levels>()
             .init_resource::<Textures>()
-            .init_resource::<BlockAttributes>()
-            .init_resource::<TileMap>()
-            .init_resource::<Queue>()
-            .init_resource::<Constraints>()
-            .init_resource::<WorldRecorder>()
-            .add_startup_system(setup_images)
+            .init_resource::<FacingTextures>()
+            .init_resource::<GridLayout>()
+            .init_resource::<Simulation>()
+            .init_resource::<ObjectEntities>()
+            .init_resource::<InputSettings>()
+            .init_resource::<TurnInput>()
+            .add_startup_system(start_loading_levels)
+            .add_system(reload_changed_levels)
+            .add_system_to_stage(CoreStage::PreUpdate, queue_turn_input.after(bevy::input::InputSystem))
+            .add_system_set(
+                SystemSet::on_update(AppState::Loading)
+                    .with_system(check_levels_loaded)
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Loading)
+                    .with_system(setup_images)
+            )
             .add_system_set(
                 SystemSet::on_enter(AppState::MainArea)
                     .with_system(change_level_to_main)
                     .with_system(setup_world.after(change_level_to_main))
+                    .with_system(clear_turn_input)
             )
             .add_system_set(
                 SystemSet::on_update(AppState::MainArea)
                     .with_system(apply_mover)
-                    .with_system(change_block_texture)
-                    .with_system(apply_constraints.before(apply_mover))
-                    .with_system(map_tiles.after(apply_mover))
-                    .with_system(record_world.after(apply_attributes).before(apply_queue))
-                    .with_system(apply_attributes.after(map_tiles))
-                    .with_system(apply_queue.after(apply_attributes))
-                    .with_system(evaluate_text.after(apply_attributes).before(apply_queue))
-                    .with_system(apply_record_to_world.after(apply_attributes).before(apply_queue).after(record_world))
+                    .with_system(take_turn.after(apply_mover))
+                    .with_system(sync_world.after(take_turn))
+                    .with_system(apply_grid_pos.after(sync_world))
+                    .with_system(change_block_texture.after(sync_world))
                     .with_system(check_if_level_changed)
             )
             .add_system_set(
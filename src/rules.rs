@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use crate::logic_types::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verb {
+    Is,
+    Has,
+    Make,
+}
+
+// NOT ROCK is every object in the level other than rocks, text isn't included.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Subject {
+    Noun(Block),
+    Not(Block),
+}
+
+impl Subject {
+    pub fn applies_to(&self, block: Block) -> bool {
+        match self {
+            Subject::Noun(noun) => *noun == block,
+            Subject::Not(noun) => *noun != block && matches!(block, Block::Object(_)),
+        }
+    }
+}
+
+// what a rule gives its subject, either another noun to turn into or a property.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RuleObject {
+    Noun(Block),
+    Property(Attribute),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConditionKind {
+    // shares its tile with the target
+    On,
+    // the target is on its tile or one of the eight around it
+    Near,
+    // the target is on the tile it's facing
+    Facing,
+    // nothing else is on its tile
+    Lonely,
+    // painted that color, or hidden, as of the end of the last turn
+    Tint(Tint),
+    Hidden,
+}
+
+// what has to be true of an object for a rule to apply to it, checked for every object on its own.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Condition {
+    pub kind: ConditionKind,
+    pub target: Option<Subject>,
+    pub negated: bool,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Rule {
+    pub subject: Subject,
+    pub verb: Verb,
+    pub object: RuleObject,
+    // ROCK IS NOT PUSH, see `Simulation::resolve_object` for how it cancels ROCK IS PUSH
+    pub negated: bool,
+    pub conditions: Vec<Condition>,
+}
+
+// a text tile by the part it plays in a sentence.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Token {
+    Noun(Block),
+    Verb(Verb),
+    Property(Attribute),
+    And,
+    Not,
+    Condition(ConditionKind),
+}
+
+impl Token {
+    pub fn from_text(text: TextBlock) -> Self {
+        match text {
+            TextBlock::Noun(noun) => Token::Noun(Block::Object(noun)),
+            TextBlock::TextNoun => Token::Noun(Block::Text),
+            TextBlock::Is => Token::Verb(Verb::Is),
+            TextBlock::Has => Token::Verb(Verb::Has),
+            TextBlock::Make => Token::Verb(Verb::Make),
+            TextBlock::You => Token::Property(Attribute::You),
+            TextBlock::Push => Token::Property(Attribute::Push),
+            TextBlock::Stop => Token::Property(Attribute::Stop),
+            TextBlock::Win => Token::Property(Attribute::Win),
+            TextBlock::Sink => Token::Property(Attribute::Sink),
+            TextBlock::Move => Token::Property(Attribute::Move),
+            TextBlock::Pull => Token::Property(Attribute::Pull),
+            TextBlock::Defeat => Token::Property(Attribute::Defeat),
+            TextBlock::Hot => Token::Property(Attribute::Hot),
+            TextBlock::Melt => Token::Property(Attribute::Melt),
+            TextBlock::Open => Token::Property(Attribute::Open),
+            TextBlock::Shut => Token::Property(Attribute::Shut),
+            TextBlock::Float => Token::Property(Attribute::Float),
+            TextBlock::Shift => Token::Property(Attribute::Shift),
+            TextBlock::Tele => Token::Property(Attribute::Tele),
+            TextBlock::Swap => Token::Property(Attribute::Swap),
+            TextBlock::Weak => Token::Property(Attribute::Weak),
+            TextBlock::More => Token::Property(Attribute::More),
+            TextBlock::Direction(facing) => Token::Property(Attribute::Face(facing)),
+            TextBlock::Fall => Token::Property(Attribute::Fall),
+            TextBlock::Tint(tint) => Token::Property(Attribute::Tint(tint)),
+            TextBlock::Hide => Token::Property(Attribute::Hide),
+            TextBlock::And => Token::And,
+            TextBlock::Not => Token::Not,
+            TextBlock::On => Token::Condition(ConditionKind::On),
+            TextBlock::Near => Token::Condition(ConditionKind::Near),
+            TextBlock::Facing => Token::Condition(ConditionKind::Facing),
+            TextBlock::Lonely => Token::Condition(ConditionKind::Lonely),
+        }
+    }
+}
+
+// the text of a level by tile, only the first text on a tile is read.
+#[derive(Default, Debug)]
+pub struct TextGrid {
+    tiles: HashMap<GridPos, TextBlock>,
+}
+
+impl TextGrid {
+    pub fn insert(&mut self, pos: GridPos, text: TextBlock) {
+        self.tiles.entry(pos).or_insert(text);
+    }
+
+    pub fn get(&self, pos: GridPos) -> Option<TextBlock> {
+        self.tiles.get(&pos).copied()
+    }
+
+    // every unbroken run of text read left to right or top to bottom, in reading order.
+    pub fn lines(&self) -> Vec<Vec<TextBlock>> {
+        let mut starts = Vec::new();
+
+        for pos in self.tiles.keys() {
+            for (direction, backwards) in [(BlockDirection::Right, BlockDirection::Left), (BlockDirection::Down, BlockDirection::Up)] {
+                if self.get(pos.offset(backwards)).is_none() {
+                    starts.push((*pos, direction));
+                }
+            }
+        }
+
+        starts.sort_by_key(|(pos, direction)| (-pos.y, pos.x, *direction == BlockDirection::Down));
+
+        starts.into_iter().map(|(start, direction)| {
+            let mut line = Vec::new();
+            let mut pos = start;
+
+            while let Some(text) = self.get(pos) {
+                line.push(text);
+                pos = pos.offset(direction);
+            }
+
+            line
+        }).collect()
+    }
+}
+
+pub fn parse_rules(grid: &TextGrid) -> Vec<Rule> {
+    grid.lines().iter().flat_map(|line| parse_line(line)).collect()
+}
+
+// a line can hold several sentences, even overlapping ones like ROCK IS WALL IS STOP where
+// the object of one is the subject of the next. The words before a sentence's verb belong to it
+// though, ROCK ON PATH IS WIN doesn't also mean PATH IS WIN.
+pub fn parse_line(line: &[TextBlock]) -> Vec<Rule> {
+    let tokens: Vec<Token> = line.iter().map(|text| Token::from_text(*text)).collect();
+    let mut rules = Vec::new();
+    let mut claimed_until = 0;
+
+    for start in 0..tokens.len() {
+        if start < claimed_until { continue; }
+
+        let mut parser = Parser::new(&tokens[start..]);
+
+        if let Some(sentence) = parser.sentence() {
+            claimed_until = start + parser.verb_index;
+            rules.extend(sentence);
+        }
+    }
+
+    rules
+}
+
+// sentence = prefix* subjects conditions? verb objects
+// prefix = NOT* (LONELY | RED | BLUE | GREEN | HIDE)
+// subjects = NOT* noun (AND NOT* noun)*
+// conditions = condition (AND condition)*
+// condition = NOT* (ON | NEAR | FACING) NOT* noun (AND NOT* noun)*
+// objects = object (AND object)*
+// object = NOT* (noun | property), only IS can take a property
+struct Parser<'a> {
+    tokens: &'a [Token],
+    index: usize,
+    verb_index: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(tokens: &'a [Token]) -> Self {
+        Self { tokens, index: 0, verb_index: 0 }
+    }
+
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.index).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.index += 1;
+        token
+    }
+
+    // reads `item` separated by AND, stopping before an AND that isn't followed by another item.
+    fn list<T>(&mut self, item: impl Fn(&mut Self) -> Option<T>) -> Option<Vec<T>> {
+        let mut items = vec![item(self)?];
+
+        while self.peek() == Some(Token::And) {
+            let before_and = self.index;
+            self.index += 1;
+
+            match item(self) {
+                Some(next) => items.push(next),
+                None => {
+                    self.index = before_and;
+                    break;
+                }
+            }
+        }
+
+        Some(items)
+    }
+
+    fn sentence(&mut self) -> Option<Vec<Rule>> {
+        let mut conditions = self.prefixes();
+        let subjects = self.list(Self::subject)?;
+        conditions.extend(self.conditions()?);
+
+        self.verb_index = self.index;
+        let verb = self.verb()?;
+        let objects = self.list(|parser| parser.object(verb))?;
+
+        // every subject gets every object.
+        let mut rules = Vec::new();
+
+        for subject in &subjects {
+            for object in &objects {
+                let (object, negated) = *object;
+
+                rules.push(Rule {
+                    subject: *subject,
+                    verb,
+                    object,
+                    negated,
+                    conditions: conditions.clone(),
+                });
+            }
+        }
+
+        Some(rules)
+    }
+
+    // NOT NOT cancels itself out.
+    fn not(&mut self) -> bool {
+        let mut negated = false;
+
+        while self.peek() == Some(Token::Not) {
+            negated = !negated;
+            self.index += 1;
+        }
+
+        negated
+    }
+
+    // none of the conditions in front of the subject have a target.
+    fn prefixes(&mut self) -> Vec<Condition> {
+        let mut conditions = Vec::new();
+
+        loop {
+            let start = self.index;
+            let negated = self.not();
+
+            let kind = match self.peek() {
+                Some(Token::Condition(ConditionKind::Lonely)) => ConditionKind::Lonely,
+                Some(Token::Property(Attribute::Tint(tint))) => ConditionKind::Tint(tint),
+                Some(Token::Property(Attribute::Hide)) => ConditionKind::Hidden,
+
+                _ => {
+                    self.index = start;
+                    return conditions;
+                }
+            };
+
+            self.index += 1;
+
+            conditions.push(Condition {
+                kind,
+                target: None,
+                negated,
+            });
+        }
+    }
+
+    fn conditions(&mut self) -> Option<Vec<Condition>> {
+        let mut conditions = Vec::new();
+
+        if !self.at_condition() {
+            return Some(conditions);
+        }
+
+        loop {
+            let negated = self.not();
+
+            // LONELY has no target so it can only come before the subject.
+            let kind = match self.next()? {
+                Token::Condition(kind @ (ConditionKind::On | ConditionKind::Near | ConditionKind::Facing)) => kind,
+                _ => return None,
+            };
+
+            // ON ROCK AND WALL needs both, so each target is a condition of its own.
+            for target in self.list(Self::subject)? {
+                conditions.push(Condition {
+                    kind,
+                    target: Some(target),
+                    negated,
+                });
+            }
+
+            // an AND followed by a noun was read as another target above, here it can only join another condition.
+            if self.peek() != Some(Token::And) {
+                return Some(conditions);
+            }
+
+            self.index += 1;
+
+            if !self.at_condition() {
+                return None;
+            }
+        }
+    }
+
+    fn at_condition(&self) -> bool {
+        let nots = self.tokens.iter()
+            .skip(self.index)
+            .take_while(|token| **token == Token::Not)
+            .count();
+
+        matches!(self.tokens.get(self.index + nots), Some(Token::Condition(_)))
+    }
+
+    fn subject(&mut self) -> Option<Subject> {
+        let negated = self.not();
+
+        match self.next()? {
+            Token::Noun(noun) if negated => Some(Subject::Not(noun)),
+            Token::Noun(noun) => Some(Subject::Noun(noun)),
+            _ => None,
+        }
+    }
+
+    fn verb(&mut self) -> Option<Verb> {
+        match self.next()? {
+            Token::Verb(verb) => Some(verb),
+            _ => None,
+        }
+    }
+
+    fn object(&mut self, verb: Verb) -> Option<(RuleObject, bool)> {
+        let negated = self.not();
+
+        match self.next()? {
+            Token::Noun(noun) => Some((RuleObject::Noun(noun), negated)),
+            Token::Property(attribute) if verb == Verb::Is => Some((RuleObject::Property(attribute), negated)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,1229 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::{logic_types::*, block_registry::BlockRegistry, level_data::LevelData, rules::*};
+
+// MORE stops growing once a level holds this many objects, every one of them ends up in each undo snapshot.
+pub const MORE_LIMIT: usize = 2000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct ObjectId(pub u32);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Object {
+    pub block: Block,
+    pub text: Option<TextBlock>,
+    pub pos: GridPos,
+    pub flip_x: bool,
+    // the way it last moved by itself, what FACING looks at and MOVE walks
+    pub facing: Facing,
+    // how RED, BLUE, GREEN and HIDE left it at the end of the last turn
+    pub tint: Option<Tint>,
+    pub hidden: bool,
+}
+
+impl Object {
+    // the sprite keeps looking left or right while facing up or down.
+    pub fn turn(&mut self, facing: Facing) {
+        match facing {
+            Facing::Left => self.flip_x = true,
+            Facing::Right => self.flip_x = false,
+            _ => {}
+        }
+
+        self.facing = facing;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TurnOutcome {
+    Continue,
+    Won,
+    LevelSelect(usize),
+}
+
+// every object that exists at one point in time, objects that were destroyed since are simply missing.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct WorldSnapshot {
+    pub objects: BTreeMap<ObjectId, Object>,
+}
+
+// a snapshot of the world before each turn. Undoing swaps the current world for the last one
+// and keeps it on the redo stack, taking a new turn makes the undone turns unreachable.
+#[derive(Default)]
+pub struct WorldRecorder {
+    undo_stack: Vec<WorldSnapshot>,
+    redo_stack: Vec<WorldSnapshot>,
+}
+
+impl WorldRecorder {
+    pub fn push(&mut self, snapshot: WorldSnapshot) {
+        self.undo_stack.push(snapshot);
+        self.redo_stack.clear();
+    }
+
+    pub fn undo(&mut self, current: WorldSnapshot) -> Option<WorldSnapshot> {
+        let snapshot = self.undo_stack.pop()?;
+        self.redo_stack.push(current);
+
+        Some(snapshot)
+    }
+
+    pub fn redo(&mut self, current: WorldSnapshot) -> Option<WorldSnapshot> {
+        let snapshot = self.redo_stack.pop()?;
+        self.undo_stack.push(current);
+
+        Some(snapshot)
+    }
+
+    // the world as it was before the turn that's being played.
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+// the attributes of every object this turn. Conditions like ROCK ON PATH IS PUSH only hold for some
+// of the rocks, so those are resolved for each object on its own. Objects without any conditional
+// rules all end up the same and share the attributes of their block.
+#[derive(Default)]
+pub struct ObjectAttributes {
+    pub shared: HashMap<Block, Vec<Attribute>>,
+    pub objects: HashMap<ObjectId, Vec<Attribute>>,
+}
+
+impl ObjectAttributes {
+    // whether any object has the attribute at all, the parts of a turn for properties no rule gives are skipped.
+    pub fn any(&self, attribute: Attribute) -> bool {
+        self.shared.values()
+            .chain(self.objects.values())
+            .any(|attributes| attributes.contains(&attribute))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum QueueType {
+    Move(BlockDirection),
+    ChangeBlock(Block),
+    WinOn(GridPos),
+    LevelSelect(GridPos, usize),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct QueueEntry {
+    pub id: Option<ObjectId>,
+    pub queue_type: QueueType,
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct Queue {
+    entries: Vec<QueueEntry>,
+}
+
+impl Queue {
+    // an object only gets one entry of each kind and the first one wins, except that it can turn into
+    // several blocks at once.
+    pub fn push(&mut self, id: ObjectId, queue_type: QueueType) {
+        for entry in &self.entries {
+            if Some(id) != entry.id { continue; }
+
+            let duplicate = match (queue_type, entry.queue_type) {
+                (QueueType::ChangeBlock(to), QueueType::ChangeBlock(existing)) => to == existing,
+                _ => std::mem::discriminant(&queue_type) == std::mem::discriminant(&entry.queue_type),
+            };
+
+            if duplicate { return; }
+        }
+
+        self.entries.push(QueueEntry {
+            id: Some(id),
+            queue_type
+        });
+    }
+
+    pub fn push_type(&mut self, queue_type: QueueType) {
+        self.entries.push(QueueEntry {
+            id: None,
+            queue_type,
+        });
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &QueueEntry> {
+        self.entries.iter()
+    }
+
+    pub fn reset(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn retain(&mut self, keep: impl FnMut(&QueueEntry) -> bool) {
+        self.entries.retain(keep);
+    }
+}
+
+// every object bucketed by the tile it's on. Kept up to date as objects spawn and move
+// instead of being rebuilt each turn, so looking up a tile never scans the whole level.
+#[derive(Default, Debug)]
+pub struct TileMap {
+    width: i32,
+    height: i32,
+    tiles: Vec<Vec<ObjectId>>,
+}
+
+impl TileMap {
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![Vec::new(); (width.max(0) * height.max(0)) as usize],
+        }
+    }
+
+    #[inline]
+    fn index(&self, pos: GridPos) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 || pos.x >= self.width || pos.y >= self.height {
+            return None;
+        }
+
+        Some((pos.y * self.width + pos.x) as usize)
+    }
+
+    #[inline]
+    pub fn get(&self, pos: GridPos) -> &[ObjectId] {
+        match self.index(pos) {
+            Some(index) => &self.tiles[index],
+            None => &[],
+        }
+    }
+
+    pub fn insert(&mut self, id: ObjectId, pos: GridPos) {
+        if let Some(index) = self.index(pos) {
+            self.tiles[index].push(id);
+        }
+    }
+
+    pub fn remove(&mut self, id: ObjectId, pos: GridPos) {
+        if let Some(index) = self.index(pos) {
+            self.tiles[index].retain(|tile_id| *tile_id != id);
+        }
+    }
+
+    #[inline]
+    pub fn move_to(&mut self, id: ObjectId, from: GridPos, to: GridPos) {
+        self.remove(id, from);
+        self.insert(id, to);
+    }
+
+    pub fn clear(&mut self) {
+        for tile in &mut self.tiles {
+            tile.clear();
+        }
+    }
+}
+
+// The whole puzzle without any rendering. The bevy systems drive it one turn at a time
+// and mirror `objects` onto sprites, but it can just as well be stepped from a test or a solver.
+#[derive(Default)]
+pub struct Simulation {
+    width: i32,
+    height: i32,
+    objects: BTreeMap<ObjectId, Object>,
+    next_id: u32,
+    tile_map: TileMap,
+    // what every block has regardless of the rules in the level
+    base_attributes: HashMap<Block, Vec<Attribute>>,
+    rules: Vec<Rule>,
+    attributes: ObjectAttributes,
+    queue: Queue,
+    world_recorder: WorldRecorder,
+    // WEAK objects a blocked move ran into this turn, destroyed along with everything else
+    crushed: Vec<ObjectId>,
+    // objects that already moved while the queue was applied, pushed and pulled ones included
+    moved: BTreeSet<ObjectId>,
+    // nouns with a word in the registry, the only objects ROCK IS TEXT can turn into text
+    worded_nouns: BTreeSet<Noun>,
+    // the level as it was loaded, what restarting goes back to
+    initial: WorldSnapshot,
+    outcome: Option<TurnOutcome>,
+}
+
+impl Simulation {
+    pub fn new(width: i32, height: i32) -> Self {
+        let mut base_attributes = HashMap::new();
+        base_attributes.insert(Block::Text, vec![Attribute::Push]);
+
+        Self {
+            width,
+            height,
+            tile_map: TileMap::new(width, height),
+            base_attributes,
+            ..Default::default()
+        }
+    }
+
+    // tiles the registry doesn't know about are left out, `BlockRegistry::unknown_tiles` reports them.
+    pub fn from_level(level: &LevelData, registry: &BlockRegistry) -> Self {
+        let mut simulation = Self::new(level.width as i32, level.height as i32);
+
+        for (index, object) in registry.objects.iter().enumerate() {
+            if let Some(level_id) = object.level {
+                simulation.base_attributes.insert(Block::Object(Noun(index as u16)), vec![Attribute::LevelSelect(level_id)]);
+            }
+        }
+
+        for word in &registry.words {
+            if let TextBlock::Noun(noun) = word.text_block {
+                simulation.worded_nouns.insert(noun);
+            }
+        }
+
+        for tile in &level.blocks.tiles {
+            if let Some(block) = registry.block_from_tile(tile.tile_id) {
+                simulation.spawn(block, None, tile.pos);
+            }
+        }
+
+        for tile in &level.text_blocks.tiles {
+            if let Some(text_block) = registry.text_block_from_tile(tile.tile_id) {
+                simulation.spawn(Block::Text, Some(text_block), tile.pos);
+            }
+        }
+
+        // transforms written in the level happen before the first turn, and the level restarts from there.
+        simulation.evaluate_text();
+        simulation.apply_queue();
+        simulation.evaluate_text();
+        simulation.apply_appearance();
+        simulation.queue.reset();
+        simulation.initial = simulation.snapshot();
+
+        simulation
+    }
+
+    pub fn spawn(&mut self, block: Block, text: Option<TextBlock>, pos: GridPos) -> ObjectId {
+        let id = ObjectId(self.next_id);
+        self.next_id += 1;
+
+        self.objects.insert(id, Object {
+            block,
+            text,
+            pos,
+            flip_x: false,
+            facing: Facing::default(),
+            tint: None,
+            hidden: false,
+        });
+
+        self.tile_map.insert(id, pos);
+
+        id
+    }
+
+    // objects that come out of another one look the same way it did.
+    fn spawn_facing(&mut self, (block, text): (Block, Option<TextBlock>), pos: GridPos, facing: Facing) -> ObjectId {
+        let id = self.spawn(block, text, pos);
+        self.objects.get_mut(&id).unwrap().facing = facing;
+        id
+    }
+
+    // what an object of type `from` is once it's turned into `to`, TEXT means the word naming it
+    // and isn't possible for nouns without one.
+    fn transformed(&self, from: Block, to: Block) -> Option<(Block, Option<TextBlock>)> {
+        match (from, to) {
+            (Block::Object(noun), Block::Text) if self.worded_nouns.contains(&noun) => Some((Block::Text, Some(TextBlock::Noun(noun)))),
+            (_, Block::Text) => None,
+            (_, to) => Some((to, None)),
+        }
+    }
+
+    #[inline]
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    #[inline]
+    pub fn get(&self, id: ObjectId) -> Option<&Object> {
+        self.objects.get(&id)
+    }
+
+    pub fn objects(&self) -> impl Iterator<Item = (ObjectId, &Object)> {
+        self.objects.iter().map(|(id, object)| (*id, object))
+    }
+
+    pub fn attributes(&self, id: ObjectId) -> &[Attribute] {
+        match self.objects.get(&id) {
+            Some(object) => self.object_attributes(id, object),
+            None => &[],
+        }
+    }
+
+    // saves looking the object up again when it's at hand anyway.
+    fn object_attributes(&self, id: ObjectId, object: &Object) -> &[Attribute] {
+        let attributes = self.attributes.objects.get(&id)
+            .or_else(|| self.attributes.shared.get(&object.block));
+
+        match attributes {
+            Some(attributes) => attributes,
+            None => &[],
+        }
+    }
+
+    #[inline]
+    pub fn has_attribute(&self, id: ObjectId, attribute: Attribute) -> bool {
+        self.attributes(id).contains(&attribute)
+    }
+
+    #[inline]
+    pub fn in_bounds(&self, pos: GridPos) -> bool {
+        pos.x >= 0 && pos.y >= 0 && pos.x < self.width && pos.y < self.height
+    }
+
+    // plays a single turn, `BlockDirection::None` lets everything but YOU act.
+    pub fn step(&mut self, direction: BlockDirection) -> TurnOutcome {
+        let before = self.snapshot();
+        self.outcome = None;
+
+        self.queue.reset();
+        self.apply_attributes(direction);
+        self.apply_queue();
+        self.apply_move();
+
+        self.queue.reset();
+        self.queue_shifts();
+        self.apply_queue();
+        self.apply_tele(&before);
+        self.apply_fall();
+
+        self.queue.reset();
+        self.evaluate_text();
+        self.destroy_objects();
+        self.queue_interactions();
+        self.apply_queue();
+
+        self.queue.reset();
+        let mut spawned = self.make_objects();
+        spawned.extend(self.grow_more());
+
+        // what was just made becomes whatever it IS this turn too, the rest already changed once and waits for the next.
+        self.evaluate_text();
+        self.queue.retain(|entry| entry.id.is_some_and(|id| spawned.contains(&id)));
+        self.apply_queue();
+
+        // worked out again so the next turn starts from whatever this one changed.
+        self.evaluate_text();
+        self.apply_appearance();
+        self.queue.reset();
+
+        // walking into a wall or waiting with nothing around changes nothing, so there's nothing to undo either.
+        if self.objects != before.objects {
+            self.world_recorder.push(before);
+        }
+
+        self.outcome.unwrap_or(TurnOutcome::Continue)
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.world_recorder.undo(self.snapshot()) {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        match self.world_recorder.redo(self.snapshot()) {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // goes back to how the level started, as its own turn so it can be undone like any other.
+    pub fn restart(&mut self) -> bool {
+        if self.objects == self.initial.objects {
+            return false;
+        }
+
+        let snapshot = self.snapshot();
+        self.world_recorder.push(snapshot);
+        self.restore(self.initial.clone());
+        true
+    }
+
+    pub fn snapshot(&self) -> WorldSnapshot {
+        WorldSnapshot {
+            objects: self.objects.clone(),
+        }
+    }
+
+    // ids are never handed out twice, so an object brought back by undo keeps its id
+    // and anything spawned after it can't be mistaken for it.
+    fn restore(&mut self, snapshot: WorldSnapshot) {
+        self.objects = snapshot.objects;
+
+        self.map_tiles();
+        self.evaluate_text();
+        self.queue.reset();
+    }
+
+    // rebuilds the tile map from scratch, only needed when many objects jump at once like on undo.
+    pub fn map_tiles(&mut self) {
+        self.tile_map.clear();
+
+        for (id, object) in &self.objects {
+            self.tile_map.insert(*id, object.pos);
+        }
+    }
+
+    pub fn occupants(&self, pos: GridPos) -> impl Iterator<Item = (ObjectId, &Object)> {
+        self.tile_map.get(pos).iter().map(|id| (*id, &self.objects[id]))
+    }
+
+    // FLOAT objects and the ones on the ground don't interact, only the objects on the same one of these
+    // two layers can win, sink, defeat or melt each other.
+    pub fn layer_occupants(&self, pos: GridPos, floating: bool) -> impl Iterator<Item = (ObjectId, &Object)> {
+        self.occupants(pos).filter(move |(id, object)| self.object_attributes(*id, object).contains(&Attribute::Float) == floating)
+    }
+
+    pub fn floats(&self, id: ObjectId) -> bool {
+        self.has_attribute(id, Attribute::Float)
+    }
+
+    #[inline]
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    fn despawn(&mut self, id: ObjectId) {
+        if let Some(object) = self.objects.remove(&id) {
+            self.tile_map.remove(id, object.pos);
+        }
+    }
+
+    fn place_object(&mut self, id: ObjectId, pos: GridPos) {
+        if let Some(object) = self.objects.get_mut(&id) {
+            let from = object.pos;
+            object.pos = pos;
+
+            self.tile_map.move_to(id, from, pos);
+        }
+    }
+
+    fn move_object(&mut self, id: ObjectId, direction: BlockDirection) {
+        if let Some(object) = self.objects.get_mut(&id) {
+            let from = object.pos;
+            object.pos = from.offset(direction);
+
+            self.tile_map.move_to(id, from, object.pos);
+        }
+    }
+
+    pub fn evaluate_text(&mut self) {
+        let mut text_grid = TextGrid::default();
+
+        for object in self.objects.values() {
+            if let (Block::Text, Some(text_block)) = (object.block, object.text) {
+                text_grid.insert(object.pos, text_block);
+            }
+        }
+
+        self.rules = parse_rules(&text_grid);
+        self.resolve_attributes();
+    }
+
+    // Goes through every rule for every object, so conditions are checked against where each one is right now.
+    fn resolve_attributes(&mut self) {
+        let mut attributes = ObjectAttributes::default();
+        let mut shared_transforms = HashMap::<Block, Vec<Block>>::new();
+        let mut transforms = Vec::<(ObjectId, Block)>::new();
+        let mut conditional_blocks = HashMap::<Block, bool>::new();
+
+        for (id, object) in &self.objects {
+            let conditional = *conditional_blocks.entry(object.block).or_insert_with(|| {
+                self.rules.iter().any(|rule| rule.subject.applies_to(object.block) && !rule.conditions.is_empty())
+            });
+
+            if conditional {
+                let (object_attributes, object_transforms) = self.resolve_object(*id, object);
+
+                attributes.objects.insert(*id, object_attributes);
+                transforms.extend(object_transforms.into_iter().map(|to| (*id, to)));
+                continue;
+            }
+
+            let block_transforms = shared_transforms.entry(object.block).or_insert_with(|| {
+                let (block_attributes, block_transforms) = self.resolve_object(*id, object);
+
+                attributes.shared.insert(object.block, block_attributes);
+                block_transforms
+            });
+
+            transforms.extend(block_transforms.iter().map(|to| (*id, *to)));
+        }
+
+        self.attributes = attributes;
+
+        for (id, to) in transforms {
+            self.queue.push(id, QueueType::ChangeBlock(to));
+        }
+    }
+
+    // Negated rules always win over their positive version, no matter where the positive one comes from or
+    // where either is written, ROCK IS NOT PUSH even takes away the PUSH text has by default.
+    // ROCK IS NOT WALL cancels ROCK IS WALL, and ROCK IS NOT ROCK keeps rocks from turning into anything.
+    // ROCK IS ROCK does too, it's what a rock already is so nothing else can take its place. Otherwise
+    // every noun a rock is turns it into one of each, ROCK IS WALL AND FLAG splits it in two.
+    fn resolve_object(&self, id: ObjectId, object: &Object) -> (Vec<Attribute>, Vec<Block>) {
+        let mut attributes = self.base_attributes.get(&object.block).cloned().unwrap_or_default();
+        let mut removed_attributes = Vec::new();
+        let mut transforms = Vec::new();
+        let mut cancelled_transforms = Vec::new();
+
+        for rule in &self.rules {
+            if !rule.subject.applies_to(object.block) { continue; }
+            if !rule.conditions.iter().all(|condition| self.condition_holds(id, object, condition)) { continue; }
+
+            let attribute = match (rule.verb, rule.object) {
+                (Verb::Is, RuleObject::Noun(to)) => {
+                    match rule.negated {
+                        true => cancelled_transforms.push(to),
+                        false if !transforms.contains(&to) => transforms.push(to),
+                        false => {}
+                    }
+
+                    continue;
+                }
+
+                (Verb::Is, RuleObject::Property(attribute)) => attribute,
+                (Verb::Has, RuleObject::Noun(block)) => Attribute::Has(block),
+                (Verb::Make, RuleObject::Noun(block)) => Attribute::Make(block),
+
+                // the parser only gives HAS and MAKE nouns
+                (_, RuleObject::Property(_)) => continue,
+            };
+
+            match rule.negated {
+                true => removed_attributes.push(attribute),
+                false => attributes.push(attribute),
+            }
+        }
+
+        attributes.retain(|attribute| !removed_attributes.contains(attribute));
+
+        if cancelled_transforms.contains(&object.block) || transforms.contains(&object.block) {
+            transforms.clear();
+        }
+
+        transforms.retain(|to| !cancelled_transforms.contains(to));
+
+        (attributes, transforms)
+    }
+
+    fn condition_holds(&self, id: ObjectId, object: &Object, condition: &Condition) -> bool {
+        let is_target = |(other_id, other): (ObjectId, &Object)| {
+            other_id != id && condition.target.is_none_or(|target| target.applies_to(other.block))
+        };
+
+        let holds = match condition.kind {
+            ConditionKind::On => self.occupants(object.pos).any(is_target),
+            ConditionKind::Facing => self.occupants(object.pos.offset(object.facing.direction())).any(is_target),
+            ConditionKind::Lonely => !self.occupants(object.pos).any(is_target),
+            ConditionKind::Tint(tint) => object.tint == Some(tint),
+            ConditionKind::Hidden => object.hidden,
+
+            ConditionKind::Near => (-1..=1).any(|y| (-1..=1).any(|x| {
+                self.occupants(GridPos::new(object.pos.x + x, object.pos.y + y)).any(is_target)
+            })),
+        };
+
+        holds != condition.negated
+    }
+
+    fn apply_attributes(&mut self, direction: BlockDirection) {
+        if direction == BlockDirection::None { return; }
+
+        let mut players = Vec::new();
+
+        for (id, object) in &self.objects {
+            if self.object_attributes(*id, object).contains(&Attribute::You) {
+                players.push(*id);
+            }
+        }
+
+        for id in players {
+            if let Some(facing) = Facing::from_direction(direction) {
+                self.objects.get_mut(&id).unwrap().turn(facing);
+            }
+
+            self.queue.push(id, QueueType::Move(direction));
+        }
+    }
+
+    // MOVE objects walk once everything YOU has moved, one after the other. One that's blocked turns
+    // around and tries the other way within the same turn, staying put if that's blocked too.
+    fn apply_move(&mut self) {
+        if !self.attributes.any(Attribute::Move) { return; }
+
+        self.moved.clear();
+        let mut movers = Vec::new();
+
+        for (id, object) in &self.objects {
+            if self.object_attributes(*id, object).contains(&Attribute::Move) {
+                movers.push(*id);
+            }
+        }
+
+        for id in movers {
+            let facing = self.objects[&id].facing;
+
+            if self.try_move(id, facing.direction()) { continue; }
+
+            self.objects.get_mut(&id).unwrap().turn(facing.reversed());
+            self.try_move(id, facing.reversed().direction());
+        }
+    }
+
+    // SHIFT carries everything else on its tile the way it's facing and turns it that way too. An object on
+    // more than one SHIFT only goes with the first, like any other queued move.
+    fn queue_shifts(&mut self) {
+        if !self.attributes.any(Attribute::Shift) { return; }
+
+        let mut shifted = Vec::new();
+        let mut seen = BTreeSet::new();
+
+        for (id, object) in &self.objects {
+            let attributes = self.object_attributes(*id, object);
+
+            if !attributes.contains(&Attribute::Shift) { continue; }
+
+            for (other_id, _) in self.layer_occupants(object.pos, attributes.contains(&Attribute::Float)) {
+                if other_id != *id && seen.insert(other_id) {
+                    shifted.push((other_id, object.facing));
+                }
+            }
+        }
+
+        for (id, facing) in shifted {
+            self.objects.get_mut(&id).unwrap().turn(facing);
+            self.queue.push(id, QueueType::Move(facing.direction()));
+        }
+    }
+
+    // Objects that moved onto a TELE this turn, compared to `before` as the turn started, come out of the next TELE of the
+    // same kind, next by id and wrapping around to the first, so a turn always plays out the same way and undo has nothing random to undo.
+    fn apply_tele(&mut self, before: &WorldSnapshot) {
+        if !self.attributes.any(Attribute::Tele) { return; }
+
+        let mut teles = Vec::new();
+
+        for (id, object) in &self.objects {
+            let attributes = self.object_attributes(*id, object);
+
+            if attributes.contains(&Attribute::Tele) {
+                teles.push((*id, object.block, object.pos, attributes.contains(&Attribute::Float)));
+            }
+        }
+
+        let mut sent = Vec::<(ObjectId, GridPos)>::new();
+
+        for (index, (tele_id, block, pos, floating)) in teles.iter().enumerate() {
+            let destination = teles.iter()
+                .cycle()
+                .skip(index + 1)
+                .take(teles.len() - 1)
+                .find(|(_, other_block, other_pos, _)| other_block == block && other_pos != pos);
+
+            let destination = match destination {
+                Some((_, _, destination, _)) => *destination,
+                None => continue,
+            };
+
+            for (id, object) in self.layer_occupants(*pos, *floating) {
+                // the other TELEs of its kind stay where they are.
+                if id == *tele_id || object.block == *block { continue; }
+
+                let entered = before.objects.get(&id).is_none_or(|before| before.pos != object.pos);
+
+                if entered && !sent.iter().any(|(sent_id, _)| *sent_id == id) {
+                    sent.push((id, destination));
+                }
+            }
+        }
+
+        for (id, pos) in sent {
+            self.place_object(id, pos);
+        }
+    }
+
+    // FALL objects drop a tile at a time, lowest id first, until none of them can go any further. One landing on
+    // another that's still falling keeps going once that one has moved out of the way. Nothing is pushed.
+    fn apply_fall(&mut self) {
+        if !self.attributes.any(Attribute::Fall) { return; }
+
+        let mut fallers = Vec::new();
+
+        for (id, object) in &self.objects {
+            if self.object_attributes(*id, object).contains(&Attribute::Fall) {
+                fallers.push(*id);
+            }
+        }
+
+        loop {
+            let mut fell = false;
+
+            for id in &fallers {
+                let below = self.objects[id].pos.offset(BlockDirection::Down);
+
+                if self.in_bounds(below) && !self.is_blocked(below) {
+                    self.move_object(*id, BlockDirection::Down);
+                    fell = true;
+                }
+            }
+
+            if !fell { break; }
+        }
+    }
+
+    fn is_blocked(&self, pos: GridPos) -> bool {
+        self.occupants(pos).any(|(id, object)| {
+            self.object_attributes(id, object).iter().any(|attribute| matches!(attribute, Attribute::Stop | Attribute::Push))
+        })
+    }
+
+    // UP, DOWN, LEFT and RIGHT turn objects the way they say, and the colors and HIDE are kept on the objects
+    // so undo brings them back and the rules of the next turn can check them. The first rule wins if there's more than one.
+    fn apply_appearance(&mut self) {
+        let facings = [Facing::Right, Facing::Left, Facing::Up, Facing::Down];
+        let tints = [Tint::Red, Tint::Blue, Tint::Green];
+
+        let turns = facings.iter().any(|facing| self.attributes.any(Attribute::Face(*facing)));
+        let paints = tints.iter().any(|tint| self.attributes.any(Attribute::Tint(*tint)))
+            || self.attributes.any(Attribute::Hide)
+            || self.objects.values().any(|object| object.tint.is_some() || object.hidden);
+
+        if !turns && !paints { return; }
+
+        let mut changes = Vec::new();
+
+        for (id, object) in &self.objects {
+            let attributes = self.object_attributes(*id, object);
+
+            let facing = attributes.iter().find_map(|attribute| match attribute {
+                Attribute::Face(facing) => Some(*facing),
+                _ => None,
+            });
+
+            let tint = attributes.iter().find_map(|attribute| match attribute {
+                Attribute::Tint(tint) => Some(*tint),
+                _ => None,
+            });
+
+            changes.push((*id, facing, tint, attributes.contains(&Attribute::Hide)));
+        }
+
+        let mut repainted = false;
+
+        for (id, facing, tint, hidden) in changes {
+            let object = self.objects.get_mut(&id).unwrap();
+
+            if let Some(facing) = facing {
+                object.turn(facing);
+            }
+
+            repainted |= object.tint != tint || object.hidden != hidden;
+            object.tint = tint;
+            object.hidden = hidden;
+        }
+
+        // RED ROCK IS PUSH goes by the colors the rocks have now.
+        if repainted {
+            self.evaluate_text();
+        }
+    }
+
+    // moves the object along with everything it pushes and pulls, returns false if it's blocked.
+    fn try_move(&mut self, id: ObjectId, direction: BlockDirection) -> bool {
+        let pos = match self.objects.get(&id) {
+            Some(object) => object.pos,
+            None => return false,
+        };
+
+        let mut pushables = vec![id];
+
+        if !self.check_for_pushable_tiles(&mut pushables, pos, direction) {
+            self.crush_weak(&pushables, direction);
+            return false;
+        }
+
+        for id in pushables {
+            self.move_object(id, direction);
+            self.moved.insert(id);
+        }
+
+        self.swap_with(id, pos, direction);
+        self.pull_behind(pos, direction);
+
+        true
+    }
+
+    // the WEAK objects pushed into whatever blocked them break, as do WEAK STOP objects in the way.
+    fn crush_weak(&mut self, pushables: &[ObjectId], direction: BlockDirection) {
+        let blocked_at = self.objects[pushables.last().unwrap()].pos.offset(direction);
+        let mut crushed = Vec::new();
+
+        for id in &pushables[1..] {
+            if self.has_attribute(*id, Attribute::Weak) {
+                crushed.push(*id);
+            }
+        }
+
+        for (id, object) in self.occupants(blocked_at) {
+            let attributes = self.object_attributes(id, object);
+
+            if attributes.contains(&Attribute::Weak) && attributes.contains(&Attribute::Stop) {
+                crushed.push(id);
+            }
+        }
+
+        self.crushed.extend(crushed);
+    }
+
+    // Whatever is still on the tile an object moved onto goes back to the tile it left, if either of them is SWAP.
+    // Nothing is pushed either way.
+    fn swap_with(&mut self, id: ObjectId, from: GridPos, direction: BlockDirection) {
+        let mover_swaps = self.has_attribute(id, Attribute::Swap);
+        let mut swapped = Vec::new();
+
+        for (other_id, other) in self.occupants(from.offset(direction)) {
+            if other_id == id { continue; }
+
+            if mover_swaps || self.object_attributes(other_id, other).contains(&Attribute::Swap) {
+                swapped.push(other_id);
+            }
+        }
+
+        for other_id in swapped {
+            self.place_object(other_id, from);
+        }
+    }
+
+    // PULL objects right behind the tile something left follow it in, as if they moved themselves. So they can be
+    // blocked or push things of their own, and each one that gets there pulls whatever is behind it in turn.
+    // Ones that already moved stay where they are.
+    fn pull_behind(&mut self, pos: GridPos, direction: BlockDirection) {
+        let mut pulled = Vec::new();
+
+        for (id, object) in self.occupants(pos.offset(direction.reversed())) {
+            if self.moved.contains(&id) { continue; }
+
+            if self.object_attributes(id, object).contains(&Attribute::Pull) {
+                pulled.push(id);
+            }
+        }
+
+        for id in pulled {
+            self.try_move(id, direction);
+        }
+    }
+
+    fn queue_interactions(&mut self) {
+        let mut interactions = Vec::new();
+
+        for (id, object) in &self.objects {
+            for attribute in self.object_attributes(*id, object) {
+                match attribute {
+                    Attribute::Win => interactions.push((*id, QueueType::WinOn(object.pos))),
+                    Attribute::LevelSelect(level_id) => interactions.push((*id, QueueType::LevelSelect(object.pos, *level_id))),
+
+                    _ => {}
+                }
+            }
+        }
+
+        for (id, queue_type) in interactions {
+            self.queue.push(id, queue_type);
+        }
+    }
+
+    fn apply_queue(&mut self) {
+        let queue = std::mem::take(&mut self.queue);
+        let mut transforms = BTreeMap::<ObjectId, Vec<Block>>::new();
+        self.moved.clear();
+
+        for entry in queue.iter() {
+            match entry.queue_type {
+                QueueType::WinOn(pos) => {
+                    let floating = entry.id.is_some_and(|id| self.floats(id));
+
+                    if self.layer_occupants(pos, floating).any(|(id, object)| self.object_attributes(id, object).contains(&Attribute::You)) {
+                        self.outcome = Some(TurnOutcome::Won);
+                    }
+                }
+
+                QueueType::LevelSelect(pos, level_id) => {
+                    if self.tile_map.get(pos).len() == 1 { continue; }
+
+                    let floating = entry.id.is_some_and(|id| self.floats(id));
+
+                    if self.layer_occupants(pos, floating).any(|(id, object)| self.object_attributes(id, object).contains(&Attribute::You)) && self.outcome.is_none() {
+                        self.outcome = Some(TurnOutcome::LevelSelect(level_id));
+                    }
+                }
+
+                QueueType::ChangeBlock(to) => {
+                    if let Some(id) = entry.id {
+                        transforms.entry(id).or_default().push(to);
+                    }
+                }
+
+                // an object only moves once, even if it was pushed or pulled before its own move came up.
+                QueueType::Move(direction) => {
+                    if let Some(id) = entry.id.filter(|id| !self.moved.contains(id)) {
+                        self.try_move(id, direction);
+                    }
+                }
+            }
+        }
+
+        let mut changes = Vec::new();
+
+        // objects destroyed since their rules were worked out don't turn into anything.
+        for (id, targets) in transforms {
+            let object = match self.objects.get(&id) {
+                Some(object) => object,
+                None => continue,
+            };
+
+            let appearances: Vec<(Block, Option<TextBlock>)> = targets.iter()
+                .filter_map(|to| self.transformed(object.block, *to))
+                .collect();
+
+            if !appearances.is_empty() {
+                changes.push((id, appearances));
+            }
+        }
+
+        // the object itself becomes the first block, a new one is spawned on its tile for each of the others.
+        for (id, appearances) in changes {
+            let object = self.objects.get_mut(&id).unwrap();
+            let (block, text) = appearances[0];
+
+            object.block = block;
+            object.text = text;
+
+            let copy = *object;
+
+            for (block, text) in appearances.into_iter().skip(1) {
+                let split_id = self.spawn(block, text, copy.pos);
+                let split = self.objects.get_mut(&split_id).unwrap();
+
+                split.flip_x = copy.flip_x;
+                split.facing = copy.facing;
+            }
+        }
+    }
+
+    // Everything that destroys objects sharing a tile, once all the moving is done. Destroyed objects are removed
+    // for good, undo brings them back from the snapshot taken before the turn.
+    fn destroy_objects(&mut self) {
+        let destroyers = [Attribute::Sink, Attribute::Defeat, Attribute::Hot, Attribute::Open, Attribute::Shut, Attribute::Weak];
+        let crushed = std::mem::take(&mut self.crushed);
+
+        if crushed.is_empty() && !destroyers.iter().any(|attribute| self.attributes.any(*attribute)) { return; }
+
+        let mut tiles = Vec::new();
+
+        for (id, object) in &self.objects {
+            let destroys = self.object_attributes(*id, object).iter().any(|attribute| destroyers.contains(attribute));
+
+            if destroys {
+                tiles.push(object.pos);
+            }
+        }
+
+        tiles.sort_by_key(|pos| (pos.y, pos.x));
+        tiles.dedup();
+
+        let mut destroyed: BTreeSet<ObjectId> = crushed.into_iter()
+            .filter(|id| self.objects.contains_key(id))
+            .collect();
+
+        for pos in tiles {
+            for floating in [false, true] {
+                self.destroyed_on(pos, floating, &mut destroyed);
+            }
+        }
+
+        let mut left_behind = Vec::new();
+
+        for id in &destroyed {
+            let object = &self.objects[id];
+
+            for attribute in self.object_attributes(*id, object) {
+                let appearance = match attribute {
+                    Attribute::Has(block) => self.transformed(object.block, *block),
+                    _ => None,
+                };
+
+                if let Some(appearance) = appearance {
+                    left_behind.push((appearance, object.pos, object.facing));
+                }
+            }
+        }
+
+        for id in destroyed {
+            self.despawn(id);
+        }
+
+        for (appearance, pos, facing) in left_behind {
+            self.spawn_facing(appearance, pos, facing);
+        }
+    }
+
+    fn destroyed_on(&self, pos: GridPos, floating: bool, destroyed: &mut BTreeSet<ObjectId>) {
+        let occupants: Vec<(ObjectId, &[Attribute])> = self.layer_occupants(pos, floating)
+            .map(|(id, object)| (id, self.object_attributes(id, object)))
+            .collect();
+
+        let any = |attribute: Attribute| occupants.iter().any(|(_, attributes)| attributes.contains(&attribute));
+        let with = |attribute: Attribute| occupants.iter()
+            .filter(move |(_, attributes)| attributes.contains(&attribute))
+            .map(|(id, _)| *id);
+
+        // SINK takes everything on its tile down with it, as long as something solid other than itself is there.
+        let sinks: Vec<ObjectId> = with(Attribute::Sink).collect();
+        let sunk = occupants.iter().any(|(id, attributes)| {
+            sinks.iter().any(|sink| sink != id) && attributes.iter().any(|attribute| {
+                matches!(attribute, Attribute::You | Attribute::Push | Attribute::Stop)
+            })
+        });
+
+        if sunk {
+            destroyed.extend(occupants.iter().map(|(id, _)| *id));
+        }
+
+        if any(Attribute::Defeat) {
+            destroyed.extend(with(Attribute::You));
+        }
+
+        if any(Attribute::Hot) {
+            destroyed.extend(with(Attribute::Melt));
+        }
+
+        if occupants.len() > 1 {
+            destroyed.extend(with(Attribute::Weak));
+        }
+
+        // every OPEN object takes one SHUT object with it, one that's both only destroys itself.
+        let open: Vec<ObjectId> = with(Attribute::Open).collect();
+        let shut: Vec<ObjectId> = with(Attribute::Shut).collect();
+
+        destroyed.extend(open.iter().filter(|id| shut.contains(id)));
+
+        let open_only = open.iter().filter(|id| !shut.contains(id));
+        let shut_only = shut.iter().filter(|id| !open.contains(id));
+
+        for (open_id, shut_id) in open_only.zip(shut_only) {
+            destroyed.insert(*open_id);
+            destroyed.insert(*shut_id);
+        }
+    }
+
+    fn make_objects(&mut self) -> Vec<ObjectId> {
+        let mut made = Vec::<((Block, Option<TextBlock>), GridPos, Facing)>::new();
+
+        for (id, object) in &self.objects {
+            for attribute in self.object_attributes(*id, object) {
+                let appearance = match attribute {
+                    Attribute::Make(block) => self.transformed(object.block, *block),
+                    _ => None,
+                };
+
+                let appearance = match appearance {
+                    Some(appearance) => appearance,
+                    None => continue,
+                };
+
+                let already_there = self.occupants(object.pos).any(|(_, other)| (other.block, other.text) == appearance)
+                    || made.iter().any(|(made_appearance, pos, _)| *made_appearance == appearance && *pos == object.pos);
+
+                if !already_there {
+                    made.push((appearance, object.pos, object.facing));
+                }
+            }
+        }
+
+        made.into_iter()
+            .map(|(appearance, pos, facing)| self.spawn_facing(appearance, pos, facing))
+            .collect()
+    }
+
+    // MORE objects grow into the tiles next to them that nothing STOP or PUSH and none of their own kind is on,
+    // right, up, left then down. What grows this turn only grows itself the next one.
+    fn grow_more(&mut self) -> Vec<ObjectId> {
+        if !self.attributes.any(Attribute::More) { return Vec::new(); }
+
+        let mut grown = Vec::<((Block, Option<TextBlock>), GridPos, Facing)>::new();
+
+        'objects: for (id, object) in &self.objects {
+            if !self.object_attributes(*id, object).contains(&Attribute::More) { continue; }
+
+            let appearance = (object.block, object.text);
+
+            for direction in [BlockDirection::Right, BlockDirection::Up, BlockDirection::Left, BlockDirection::Down] {
+                if self.objects.len() + grown.len() >= MORE_LIMIT {
+                    break 'objects;
+                }
+
+                let pos = object.pos.offset(direction);
+
+                if !self.in_bounds(pos) { continue; }
+
+                let taken = self.occupants(pos).any(|(other_id, other)| {
+                    (other.block, other.text) == appearance || self.object_attributes(other_id, other).iter().any(|attribute| {
+                        matches!(attribute, Attribute::Stop | Attribute::Push)
+                    })
+                });
+
+                if !taken && !grown.iter().any(|(grown_appearance, grown_pos, _)| *grown_appearance == appearance && *grown_pos == pos) {
+                    grown.push((appearance, pos, object.facing));
+                }
+            }
+        }
+
+        grown.into_iter()
+            .map(|(appearance, pos, facing)| self.spawn_facing(appearance, pos, facing))
+            .collect()
+    }
+
+    // collects everything pushed along by something leaving `pos`, returns false if the move is blocked.
+    fn check_for_pushable_tiles(&self, pushables: &mut Vec<ObjectId>, pos: GridPos, direction: BlockDirection) -> bool {
+        let pos = pos.offset(direction);
+
+        if !self.in_bounds(pos) {
+            return false;
+        }
+
+        let mut pushes_next_tile = false;
+
+        for (id, object) in self.occupants(pos) {
+            let attributes = self.object_attributes(id, object);
+
+            if attributes.contains(&Attribute::Push) {
+                pushables.push(id);
+                pushes_next_tile = true;
+            } else if attributes.contains(&Attribute::Stop) {
+                return false;
+            }
+        }
+
+        !pushes_next_tile || self.check_for_pushable_tiles(pushables, pos, direction)
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::VecDeque;
+use bevy::prelude::*;
+use crate::logic_types::BlockDirection;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TurnCommand {
+    Move(BlockDirection),
+    Wait,
+    Undo,
+    Redo,
+    Restart,
+}
+
+impl TurnCommand {
+    // restarting again just after a restart does nothing, so holding the key shouldn't repeat it either.
+    pub fn repeats(self) -> bool {
+        !matches!(self, TurnCommand::Restart)
+    }
+}
+
+// all times are in seconds.
+pub struct InputSettings {
+    pub bindings: Vec<(KeyCode, TurnCommand)>,
+    // how long a key has to be held before it starts repeating
+    pub initial_delay: f32,
+    // the time between two repeats of a held key
+    pub repeat_rate: f32,
+    // how many presses are remembered while a turn is still animating
+    pub buffer_size: usize,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        Self {
+            bindings: vec![
+                (KeyCode::A, TurnCommand::Move(BlockDirection::Left)),
+                (KeyCode::Left, TurnCommand::Move(BlockDirection::Left)),
+                (KeyCode::D, TurnCommand::Move(BlockDirection::Right)),
+                (KeyCode::Right, TurnCommand::Move(BlockDirection::Right)),
+                (KeyCode::W, TurnCommand::Move(BlockDirection::Up)),
+                (KeyCode::Up, TurnCommand::Move(BlockDirection::Up)),
+                (KeyCode::S, TurnCommand::Move(BlockDirection::Down)),
+                (KeyCode::Down, TurnCommand::Move(BlockDirection::Down)),
+                (KeyCode::Space, TurnCommand::Wait),
+                (KeyCode::T, TurnCommand::Undo),
+                (KeyCode::C, TurnCommand::Undo),
+                (KeyCode::R, TurnCommand::Redo),
+                (KeyCode::Z, TurnCommand::Redo),
+                (KeyCode::Back, TurnCommand::Restart),
+            ],
+            initial_delay: 0.25,
+            repeat_rate: 0.12,
+            buffer_size: 3,
+        }
+    }
+}
+
+// the turns the player asked for that haven't been played yet, `take_turn` plays one each time
+// the previous turn finished animating.
+#[derive(Default)]
+pub struct TurnInput {
+    queue: VecDeque<TurnCommand>,
+    held: Option<(KeyCode, TurnCommand)>,
+    repeat_timer: f32,
+}
+
+impl TurnInput {
+    pub fn press(&mut self, key: KeyCode, command: TurnCommand, settings: &InputSettings) {
+        if self.queue.len() < settings.buffer_size {
+            self.queue.push_back(command);
+        }
+
+        // the key pressed last is the one that repeats.
+        self.held = Some((key, command));
+        self.repeat_timer = settings.initial_delay;
+    }
+
+    pub fn release(&mut self, key: KeyCode) {
+        if matches!(self.held, Some((held_key, _)) if held_key == key) {
+            self.held = None;
+        }
+    }
+
+    pub fn tick(&mut self, delta_seconds: f32, settings: &InputSettings) {
+        let command = match self.held {
+            Some((_, command)) if command.repeats() => command,
+            _ => return,
+        };
+
+        self.repeat_timer -= delta_seconds;
+
+        if self.repeat_timer > 0.0 { return; }
+
+        self.repeat_timer += settings.repeat_rate;
+
+        // repeats never pile up behind a slow animation, they only fill an empty queue.
+        if self.queue.is_empty() {
+            self.queue.push_back(command);
+        }
+    }
+
+    pub fn next_turn(&mut self) -> Option<TurnCommand> {
+        self.queue.pop_front()
+    }
+
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.held = None;
+    }
+}
+
+pub fn queue_turn_input(keys: Res<Input<KeyCode>>, time: Res<Time>, settings: Res<InputSettings>, mut turn_input: ResMut<TurnInput>) {
+    for key in keys.get_just_released() {
+        turn_input.release(*key);
+    }
+
+    turn_input.tick(time.delta_seconds(), &settings);
+
+    // keys pressed on the same frame each get their own turn, in the order they're bound.
+    for (key, command) in &settings.bindings {
+        if keys.just_pressed(*key) {
+            turn_input.press(*key, *command, &settings);
+        }
+    }
+}
+
+// whatever was pressed in the last level doesn't carry over into the next one.
+pub fn clear_turn_input(mut turn_input: ResMut<TurnInput>) {
+    turn_input.clear();
+}
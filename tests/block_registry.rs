@@ -0,0 +1,95 @@
+use serde_json::{json, Value};
+use bevy_is_you::{block_registry::BlockRegistry, logic_types::*};
+
+// the smallest registry the game accepts, one object with its noun and one word.
+fn registry_json() -> Value {
+    json!({
+        "objects": [
+            { "name": "rock", "sprite": "textures/rock.png", "tile": 2, "noun": { "sprite": "textures/text rock.png", "tile": 6 } },
+        ],
+        "words": [
+            { "word": "is", "sprite": "textures/text is.png", "tile": 2 },
+        ],
+    })
+}
+
+fn rejection(registry: Value) -> String {
+    match BlockRegistry::from_json(&registry) {
+        Ok(_) => panic!("expected {} to be rejected", registry),
+        Err(error) => error.to_string(),
+    }
+}
+
+#[test]
+fn missing_objects_or_words_are_errors() {
+    for field in ["objects", "words"] {
+        let mut registry = registry_json();
+        registry.as_object_mut().unwrap().remove(field);
+
+        assert!(rejection(registry).contains(&format!("\"{}\" array", field)));
+    }
+}
+
+#[test]
+fn duplicate_object_tile_names_the_object() {
+    let mut registry = registry_json();
+    registry["objects"].as_array_mut().unwrap().push(json!({ "name": "wall", "sprite": "textures/wall.png", "tile": 2 }));
+
+    assert!(rejection(registry).contains("\"wall\" uses tile 2"));
+}
+
+#[test]
+fn duplicate_word_tile_names_the_word() {
+    let mut registry = registry_json();
+    registry["words"].as_array_mut().unwrap().push(json!({ "word": "you", "sprite": "textures/text you.png", "tile": 2 }));
+
+    assert!(rejection(registry).contains("the word \"you\" uses tile 2"));
+}
+
+#[test]
+fn unknown_word_is_an_error() {
+    let mut registry = registry_json();
+    registry["words"].as_array_mut().unwrap().push(json!({ "word": "jump", "sprite": "textures/text jump.png", "tile": 3 }));
+
+    assert!(rejection(registry).contains("\"jump\" isn't a word the game knows"));
+}
+
+#[test]
+fn bad_facing_key_names_the_object() {
+    let mut registry = registry_json();
+    registry["objects"][0]["facing"] = json!({ "north": "textures/rock north.png" });
+
+    assert!(rejection(registry).contains("\"rock\" has a sprite for \"north\""));
+}
+
+#[test]
+fn non_string_sprite_names_the_entry() {
+    let mut registry = registry_json();
+    registry["objects"][0]["sprite"] = json!(3);
+    assert!(rejection(registry).contains("\"rock\" has no \"sprite\""));
+
+    let mut registry = registry_json();
+    registry["objects"][0]["noun"]["sprite"] = json!(3);
+    assert!(rejection(registry).contains("\"rock\" has no \"sprite\""));
+
+    let mut registry = registry_json();
+    registry["objects"][0]["facing"] = json!({ "up": 3 });
+    assert!(rejection(registry).contains("\"rock\" has a \"up\" sprite that isn't a path"));
+}
+
+#[test]
+fn new_object_gets_its_text_tile() {
+    let mut registry = registry_json();
+    registry["objects"].as_array_mut().unwrap().push(json!({
+        "name": "key", "sprite": "textures/key.png", "tile": 40, "noun": { "sprite": "textures/text key.png", "tile": 41 },
+    }));
+
+    let registry = BlockRegistry::from_json(&registry).unwrap();
+    let key = registry.noun("key").unwrap();
+
+    // nothing in the code knows about keys.
+    assert_eq!(TextBlock::from_word("key"), None);
+
+    assert_eq!(registry.block_from_tile(40), Some(Block::Object(key)));
+    assert_eq!(registry.text_block_from_tile(41), Some(TextBlock::Noun(key)));
+}
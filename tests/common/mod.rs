@@ -0,0 +1,85 @@
+// builds small levels out of the objects and words in `assets/blocks.registry.json`.
+#![allow(dead_code)]
+
+use bevy_is_you::{block_registry::BlockRegistry, logic_types::*, level_data::*, simulation::*};
+
+pub fn registry() -> BlockRegistry {
+    let registry_json = serde_json::from_str(include_str!("../../assets/blocks.registry.json")).unwrap();
+    BlockRegistry::from_json(&registry_json).unwrap()
+}
+
+pub struct TestLevel {
+    pub registry: BlockRegistry,
+    level: LevelData,
+}
+
+impl TestLevel {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            registry: registry(),
+            level: LevelData {
+                width,
+                height,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn block(&self, name: &str) -> Block {
+        Block::Object(self.noun(name))
+    }
+
+    pub fn noun(&self, name: &str) -> Noun {
+        self.registry.noun(name).unwrap_or_else(|| panic!("no object named \"{}\"", name))
+    }
+
+    // a noun by its object name or any other word, as written in the registry.
+    pub fn word(&self, word: &str) -> TextBlock {
+        match self.registry.noun(word) {
+            Some(noun) => TextBlock::Noun(noun),
+            None => TextBlock::from_word(word).unwrap_or_else(|| panic!("no word \"{}\"", word)),
+        }
+    }
+
+    pub fn object(mut self, name: &str, x: i32, y: i32) -> Self {
+        let tile_id = self.registry.object(self.noun(name)).tile_id;
+        self.level.blocks.tiles.push(LevelTile { tile_id, index: 0, pos: GridPos::new(x, y) });
+        self
+    }
+
+    pub fn text(mut self, word: &str, x: i32, y: i32) -> Self {
+        let text_block = self.word(word);
+        let tile_id = self.registry.words.iter()
+            .find(|definition| definition.text_block == text_block)
+            .unwrap_or_else(|| panic!("\"{}\" has no text tile", word))
+            .tile_id;
+
+        self.level.text_blocks.tiles.push(LevelTile { tile_id, index: 0, pos: GridPos::new(x, y) });
+        self
+    }
+
+    // lays the words out left to right starting at `x`, `y`.
+    pub fn rule(mut self, words: &[&str], x: i32, y: i32) -> Self {
+        for (offset, word) in words.iter().enumerate() {
+            self = self.text(word, x + offset as i32, y);
+        }
+
+        self
+    }
+
+    pub fn build(&self) -> Simulation {
+        Simulation::from_level(&self.level, &self.registry)
+    }
+}
+
+pub fn positions_of(simulation: &Simulation, block: Block) -> Vec<GridPos> {
+    simulation.objects()
+        .filter(|(_, object)| object.block == block)
+        .map(|(_, object)| object.pos)
+        .collect()
+}
+
+// whether any object of the type has the attribute.
+pub fn block_has(simulation: &Simulation, block: Block, attribute: Attribute) -> bool {
+    simulation.objects().any(|(id, object)| object.block == block && simulation.has_attribute(id, attribute))
+}
@@ -0,0 +1,132 @@
+use serde_json::{json, Value};
+use bevy_is_you::{block_registry::BlockRegistry, level_data::*, logic_types::*};
+
+// a 2x2 tile level with both layers, `text` being the data of the "text blocks" layer.
+fn level_json(text: Value) -> Value {
+    json!({
+        "width": 32,
+        "height": 32,
+        "layers": [
+            { "name": "blocks", "gridCellWidth": 16, "gridCellHeight": 16, "gridCellsX": 2, "gridCellsY": 2, "data": [3, -1, -1, -1] },
+            { "name": "text blocks", "gridCellWidth": 16, "gridCellHeight": 16, "gridCellsX": 2, "gridCellsY": 2, "data": text },
+        ],
+    })
+}
+
+#[test]
+fn tiles_are_read_from_the_bottom_left() {
+    let level = create_level_data(level_json(json!([-1, -1, -1, 7]))).unwrap();
+
+    assert_eq!((level.width, level.height), (2, 2));
+    assert_eq!(level.blocks.tiles.iter().map(|tile| (tile.tile_id, tile.pos)).collect::<Vec<_>>(), vec![(3, GridPos::new(0, 1))]);
+    assert_eq!(level.text_blocks.tiles.iter().map(|tile| (tile.tile_id, tile.pos)).collect::<Vec<_>>(), vec![(7, GridPos::new(1, 0))]);
+    assert!(level.warnings.is_empty());
+}
+
+#[test]
+fn unknown_tiles_are_reported_by_the_registry() {
+    let registry_json = serde_json::from_str(include_str!("../assets/blocks.registry.json")).unwrap();
+    let registry = BlockRegistry::from_json(&registry_json).unwrap();
+    let level = create_level_data(level_json(json!([-1, -1, -1, 999]))).unwrap();
+
+    match registry.unknown_tiles(&level).as_slice() {
+        [LevelParseWarning::UnknownTile { layer, index, tile_id }] => {
+            assert_eq!(layer, "text blocks");
+            assert_eq!((*index, *tile_id), (3, 999));
+        }
+
+        warnings => panic!("expected an unknown tile, got {:?}", warnings),
+    }
+}
+
+#[test]
+fn missing_fields_are_errors() {
+    for field in ["width", "layers"] {
+        let mut level = level_json(json!([-1, -1, -1, -1]));
+        level.as_object_mut().unwrap().remove(field);
+
+        match create_level_data(level) {
+            Err(LevelParseError::MissingField(missing)) => assert_eq!(missing, field),
+            other => panic!("expected \"{}\" to be missing, got {:?}", field, other.map(|_| ())),
+        }
+    }
+}
+
+#[test]
+fn layer_without_data_is_an_error() {
+    let mut level = level_json(json!([-1, -1, -1, -1]));
+    level["layers"][1].as_object_mut().unwrap().remove("data");
+
+    match create_level_data(level) {
+        Err(LevelParseError::MissingData { layer }) => assert_eq!(layer, "text blocks"),
+        other => panic!("expected missing data, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn non_integer_cell_names_its_layer_and_index() {
+    let error = create_level_data(level_json(json!([-1, -1, "rock", -1]))).err().unwrap();
+
+    match &error {
+        LevelParseError::InvalidTile { layer, index, value } => {
+            assert_eq!(layer, "text blocks");
+            assert_eq!(*index, 2);
+            assert_eq!(value, &json!("rock"));
+        }
+
+        other => panic!("expected an invalid tile, got {:?}", other),
+    }
+
+    assert!(error.to_string().contains("layer \"text blocks\", cell 2"));
+}
+
+#[test]
+fn cell_count_mismatch_is_a_warning() {
+    let level = create_level_data(level_json(json!([-1, -1, -1]))).unwrap();
+
+    match level.warnings.as_slice() {
+        [LevelParseWarning::DataLengthMismatch { layer, cells_x, cells_y, data_len }] => {
+            assert_eq!(layer, "text blocks");
+            assert_eq!((*cells_x, *cells_y, *data_len), (2, 2, 3));
+        }
+
+        warnings => panic!("expected a size mismatch, got {:?}", warnings),
+    }
+}
+
+#[test]
+fn layer_wider_than_the_level_is_a_warning() {
+    // 3x2 cells fit the data exactly, but the level is only 2 tiles wide.
+    let mut level = level_json(json!([-1, -1, 7, -1, 7, -1]));
+    level["layers"][1]["gridCellsX"] = json!(3);
+
+    let level = create_level_data(level).unwrap();
+
+    assert_eq!(level.text_blocks.tiles.iter().map(|tile| (tile.tile_id, tile.pos)).collect::<Vec<_>>(), vec![(7, GridPos::new(1, 0))]);
+
+    match level.warnings.as_slice() {
+        [LevelParseWarning::LayerSizeMismatch { layer, cells_x, cells_y, width, height }] => {
+            assert_eq!(layer, "text blocks");
+            assert_eq!((*cells_x, *cells_y, *width, *height), (3, 2, 2, 2));
+        }
+
+        warnings => panic!("expected a layer size mismatch, got {:?}", warnings),
+    }
+}
+
+#[test]
+fn non_standard_grid_is_a_warning() {
+    let mut level = level_json(json!([-1, -1, -1, -1]));
+    level["layers"][0]["gridCellWidth"] = json!(8);
+
+    let level = create_level_data(level).unwrap();
+
+    match level.warnings.as_slice() {
+        [LevelParseWarning::NonStandardGrid { layer, cell_width, cell_height }] => {
+            assert_eq!(layer, "blocks");
+            assert_eq!((*cell_width, *cell_height), (8, 16));
+        }
+
+        warnings => panic!("expected a non standard grid, got {:?}", warnings),
+    }
+}
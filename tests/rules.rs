@@ -0,0 +1,254 @@
+use bevy_is_you::{logic_types::*, rules::*};
+
+const ROCK: Noun = Noun(0);
+const WALL: Noun = Noun(1);
+const FLAG: Noun = Noun(2);
+
+const PATH: Noun = Noun(3);
+
+fn word(word: &str) -> TextBlock {
+    match word {
+        "rock" => TextBlock::Noun(ROCK),
+        "wall" => TextBlock::Noun(WALL),
+        "flag" => TextBlock::Noun(FLAG),
+        "path" => TextBlock::Noun(PATH),
+        _ => TextBlock::from_word(word).unwrap(),
+    }
+}
+
+// rows are written top to bottom, `.` is an empty tile.
+fn grid(rows: &[&str]) -> TextGrid {
+    let mut grid = TextGrid::default();
+
+    for (row, line) in rows.iter().enumerate() {
+        for (column, text) in line.split_whitespace().enumerate() {
+            if text == "." { continue; }
+
+            grid.insert(GridPos::new(column as i32, (rows.len() - row - 1) as i32), word(text));
+        }
+    }
+
+    grid
+}
+
+fn rule(subject: Noun, object: RuleObject) -> Rule {
+    Rule {
+        subject: Subject::Noun(Block::Object(subject)),
+        verb: Verb::Is,
+        object,
+        negated: false,
+        conditions: Vec::new(),
+    }
+}
+
+fn property(subject: Noun, attribute: Attribute) -> Rule {
+    rule(subject, RuleObject::Property(attribute))
+}
+
+#[test]
+fn reads_horizontal_and_vertical_rules() {
+    let rules = parse_rules(&grid(&[
+        "rock is push",
+        "is   .  .",
+        "stop .  .",
+    ]));
+
+    assert_eq!(rules, vec![property(ROCK, Attribute::Push), property(ROCK, Attribute::Stop)]);
+}
+
+#[test]
+fn reads_noun_objects() {
+    let rules = parse_rules(&grid(&["rock is wall"]));
+
+    assert_eq!(rules, vec![rule(ROCK, RuleObject::Noun(Block::Object(WALL)))]);
+}
+
+#[test]
+fn ignores_incomplete_and_backwards_sentences() {
+    let rules = parse_rules(&grid(&[
+        "push is rock",
+        ".    .  .",
+        "rock is .",
+        ".    .  .",
+        "is   rock .",
+    ]));
+
+    assert!(rules.is_empty());
+}
+
+#[test]
+fn reads_overlapping_sentences() {
+    let rules = parse_rules(&grid(&["rock is wall is stop"]));
+
+    assert_eq!(rules, vec![rule(ROCK, RuleObject::Noun(Block::Object(WALL))), property(WALL, Attribute::Stop)]);
+}
+
+#[test]
+fn finds_sentences_inside_longer_lines() {
+    let rules = parse_rules(&grid(&["win flag is win stop"]));
+
+    assert_eq!(rules, vec![property(FLAG, Attribute::Win)]);
+}
+
+#[test]
+fn lines_are_broken_by_gaps() {
+    let rules = parse_rules(&grid(&["rock . is push"]));
+
+    assert!(rules.is_empty());
+}
+
+#[test]
+fn and_joins_objects() {
+    let rules = parse_rules(&grid(&["rock is push and stop"]));
+
+    assert_eq!(rules, vec![property(ROCK, Attribute::Push), property(ROCK, Attribute::Stop)]);
+}
+
+#[test]
+fn and_joins_subjects_and_objects() {
+    let rules = parse_rules(&grid(&["rock and wall is push and flag"]));
+
+    assert_eq!(rules, vec![
+        property(ROCK, Attribute::Push),
+        rule(ROCK, RuleObject::Noun(Block::Object(FLAG))),
+        property(WALL, Attribute::Push),
+        rule(WALL, RuleObject::Noun(Block::Object(FLAG))),
+    ]);
+}
+
+#[test]
+fn dangling_and_is_ignored() {
+    let rules = parse_rules(&grid(&[
+        "and rock is win and",
+        ".   .    .  .   .",
+        "rock and is push .",
+    ]));
+
+    assert_eq!(rules, vec![property(ROCK, Attribute::Win)]);
+}
+
+#[test]
+fn not_negates_objects() {
+    let rules = parse_rules(&grid(&["rock is not push and wall"]));
+
+    assert_eq!(rules, vec![
+        Rule { negated: true, ..property(ROCK, Attribute::Push) },
+        rule(ROCK, RuleObject::Noun(Block::Object(WALL))),
+    ]);
+}
+
+#[test]
+fn not_negates_subjects() {
+    let rules = parse_rules(&grid(&["not rock and wall is stop"]));
+
+    assert_eq!(rules, vec![
+        Rule { subject: Subject::Not(Block::Object(ROCK)), ..property(ROCK, Attribute::Stop) },
+        property(WALL, Attribute::Stop),
+    ]);
+}
+
+#[test]
+fn double_not_cancels_out() {
+    let rules = parse_rules(&grid(&["rock is not not push"]));
+
+    assert_eq!(rules, vec![property(ROCK, Attribute::Push)]);
+}
+
+#[test]
+fn not_subjects_cover_every_other_object() {
+    let subject = Subject::Not(Block::Object(ROCK));
+
+    assert!(subject.applies_to(Block::Object(WALL)));
+    assert!(!subject.applies_to(Block::Object(ROCK)));
+    assert!(!subject.applies_to(Block::Text));
+}
+
+fn condition(kind: ConditionKind, target: Option<Noun>) -> Condition {
+    Condition {
+        kind,
+        target: target.map(|noun| Subject::Noun(Block::Object(noun))),
+        negated: false,
+    }
+}
+
+#[test]
+fn reads_conditions() {
+    let rules = parse_rules(&grid(&["rock on path is win"]));
+
+    assert_eq!(rules, vec![Rule {
+        conditions: vec![condition(ConditionKind::On, Some(PATH))],
+        ..property(ROCK, Attribute::Win)
+    }]);
+}
+
+#[test]
+fn condition_targets_and_conditions_join_with_and() {
+    let rules = parse_rules(&grid(&["rock near path and wall and not facing flag is stop"]));
+
+    assert_eq!(rules, vec![Rule {
+        conditions: vec![
+            condition(ConditionKind::Near, Some(PATH)),
+            condition(ConditionKind::Near, Some(WALL)),
+            Condition { negated: true, ..condition(ConditionKind::Facing, Some(FLAG)) },
+        ],
+        ..property(ROCK, Attribute::Stop)
+    }]);
+}
+
+#[test]
+fn lonely_comes_before_the_subjects() {
+    let rules = parse_rules(&grid(&["not lonely rock and wall is push"]));
+    let lonely = Condition { negated: true, ..condition(ConditionKind::Lonely, None) };
+
+    assert_eq!(rules, vec![
+        Rule { conditions: vec![lonely], ..property(ROCK, Attribute::Push) },
+        Rule { conditions: vec![lonely], ..property(WALL, Attribute::Push) },
+    ]);
+}
+
+#[test]
+fn misplaced_conditions_break_the_sentence() {
+    let rules = parse_rules(&grid(&[
+        "rock lonely is push",
+        ".    .      .  .",
+        "rock on is push",
+    ]));
+
+    assert!(rules.is_empty());
+}
+
+#[test]
+fn has_and_make_only_take_nouns() {
+    let rules = parse_rules(&grid(&[
+        "rock has wall and push",
+        ".    .   .    .   .",
+        "flag make push . .",
+    ]));
+
+    assert_eq!(rules, vec![Rule { verb: Verb::Has, ..rule(ROCK, RuleObject::Noun(Block::Object(WALL))) }]);
+}
+
+#[test]
+fn adjectives_come_before_the_subjects() {
+    let rules = parse_rules(&grid(&[
+        "red rock is push",
+        ".   .    .  .",
+        "lonely not blue wall is stop",
+    ]));
+
+    let red = condition(ConditionKind::Tint(Tint::Red), None);
+    let lonely = condition(ConditionKind::Lonely, None);
+    let not_blue = Condition { negated: true, ..condition(ConditionKind::Tint(Tint::Blue), None) };
+
+    assert_eq!(rules, vec![
+        Rule { conditions: vec![red], ..property(ROCK, Attribute::Push) },
+        Rule { conditions: vec![lonely, not_blue], ..property(WALL, Attribute::Stop) },
+    ]);
+}
+
+#[test]
+fn adjectives_are_properties_after_is() {
+    let rules = parse_rules(&grid(&["rock is green and hide"]));
+
+    assert_eq!(rules, vec![property(ROCK, Attribute::Tint(Tint::Green)), property(ROCK, Attribute::Hide)]);
+}
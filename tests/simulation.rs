@@ -0,0 +1,1078 @@
+mod common;
+
+use bevy_is_you::{logic_types::*, simulation::*};
+use common::*;
+
+#[test]
+fn steps_without_a_window() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "push"], 0, 2)
+        .rule(&["flag", "is", "win"], 0, 1)
+        .object("bevy", 4, 0)
+        .object("rock", 5, 0)
+        .object("flag", 5, 1);
+
+    let mut simulation = level.build();
+
+    assert_eq!(simulation.step(BlockDirection::Right), TurnOutcome::Continue);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(5, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(6, 0)]);
+
+    assert_eq!(simulation.step(BlockDirection::Up), TurnOutcome::Won);
+}
+
+#[test]
+fn level_starts_with_its_transforms_applied() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "is", "wall"], 0, 3)
+        .rule(&["wall", "is", "stop"], 0, 2)
+        .rule(&["bevy", "is", "you"], 0, 1)
+        .object("bevy", 4, 0)
+        .object("rock", 5, 0);
+
+    let mut simulation = level.build();
+
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("wall")), vec![GridPos::new(5, 0)]);
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(4, 0)]);
+
+    // restarting doesn't bring the rock back either.
+    simulation.step(BlockDirection::Up);
+    assert!(simulation.restart());
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("wall")), vec![GridPos::new(5, 0)]);
+}
+
+#[test]
+fn and_gives_every_subject_every_property() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "and", "rock", "is", "you", "and", "win"], 0, 3)
+        .object("bevy", 0, 0)
+        .object("rock", 4, 0);
+
+    let simulation = level.build();
+
+    for name in ["bevy", "rock"] {
+        assert!(block_has(&simulation, level.block(name), Attribute::You));
+        assert!(block_has(&simulation, level.block(name), Attribute::Win));
+    }
+}
+
+#[test]
+fn not_cancels_properties() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["bevy", "is", "not", "you"], 0, 2)
+        .object("bevy", 0, 0);
+
+    let mut simulation = level.build();
+
+    assert!(!block_has(&simulation, level.block("bevy"), Attribute::You));
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(0, 0)]);
+}
+
+#[test]
+fn not_subject_applies_to_every_other_object() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["not", "rock", "is", "stop"], 0, 3)
+        .object("rock", 0, 0)
+        .object("wall", 1, 0)
+        .object("flag", 2, 0);
+
+    let simulation = level.build();
+
+    assert!(!block_has(&simulation, level.block("rock"), Attribute::Stop));
+    assert!(block_has(&simulation, level.block("wall"), Attribute::Stop));
+    assert!(block_has(&simulation, level.block("flag"), Attribute::Stop));
+    assert!(!block_has(&simulation, Block::Text, Attribute::Stop));
+}
+
+#[test]
+fn noun_is_not_itself_blocks_transforms() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["wall", "is", "rock"], 0, 3)
+        .rule(&["wall", "is", "not", "wall"], 0, 2)
+        .rule(&["bevy", "is", "you"], 0, 1)
+        .object("bevy", 0, 0)
+        .object("wall", 4, 0);
+
+    let mut simulation = level.build();
+    simulation.step(BlockDirection::Up);
+
+    assert_eq!(positions_of(&simulation, level.block("wall")), vec![GridPos::new(4, 0)]);
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+}
+
+#[test]
+fn noun_is_not_noun_cancels_that_transform() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["wall", "is", "rock", "and", "flag"], 0, 3)
+        .rule(&["wall", "is", "not", "rock"], 0, 2)
+        .rule(&["bevy", "is", "you"], 0, 1)
+        .object("bevy", 0, 0)
+        .object("wall", 6, 0);
+
+    let mut simulation = level.build();
+    simulation.step(BlockDirection::Up);
+
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(6, 0)]);
+}
+
+#[test]
+fn on_only_applies_to_objects_on_the_target() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "on", "path", "is", "stop"], 0, 3)
+        .object("rock", 0, 0)
+        .object("path", 0, 0)
+        .object("rock", 2, 0);
+
+    let simulation = level.build();
+    let stops: Vec<GridPos> = simulation.objects()
+        .filter(|(id, _)| simulation.has_attribute(*id, Attribute::Stop))
+        .map(|(_, object)| object.pos)
+        .collect();
+
+    assert_eq!(stops, vec![GridPos::new(0, 0)]);
+}
+
+#[test]
+fn conditions_are_checked_again_after_moving() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["bevy", "on", "path", "is", "win"], 0, 2)
+        .object("bevy", 0, 0)
+        .object("path", 1, 0);
+
+    let mut simulation = level.build();
+    assert!(!block_has(&simulation, level.block("bevy"), Attribute::Win));
+
+    assert_eq!(simulation.step(BlockDirection::Right), TurnOutcome::Won);
+}
+
+#[test]
+fn near_looks_at_the_surrounding_tiles() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "near", "water", "is", "push"], 0, 3)
+        .object("water", 1, 1)
+        .object("rock", 2, 0)
+        .object("rock", 4, 0);
+
+    let simulation = level.build();
+    let pushes: Vec<GridPos> = simulation.objects()
+        .filter(|(id, _)| simulation.has_attribute(*id, Attribute::Push))
+        .filter(|(_, object)| object.block == level.block("rock"))
+        .map(|(_, object)| object.pos)
+        .collect();
+
+    assert_eq!(pushes, vec![GridPos::new(2, 0)]);
+}
+
+#[test]
+fn facing_follows_the_last_move() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["bevy", "facing", "rock", "is", "win"], 0, 2)
+        .object("rock", 6, 2)
+        .object("bevy", 6, 0)
+        .object("wall", 7, 0)
+        .rule(&["wall", "is", "stop"], 0, 1);
+
+    let mut simulation = level.build();
+    assert!(!block_has(&simulation, level.block("bevy"), Attribute::Win));
+
+    // bumping into the wall still turns bevy around.
+    assert_eq!(simulation.step(BlockDirection::Right), TurnOutcome::Continue);
+    assert!(!block_has(&simulation, level.block("bevy"), Attribute::Win));
+
+    simulation.step(BlockDirection::Up);
+    assert!(block_has(&simulation, level.block("bevy"), Attribute::Win));
+}
+
+#[test]
+fn lonely_objects_are_alone_on_their_tile() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["lonely", "rock", "is", "stop"], 0, 3)
+        .object("rock", 0, 0)
+        .object("rock", 2, 0)
+        .object("path", 2, 0);
+
+    let simulation = level.build();
+    let stops: Vec<GridPos> = simulation.objects()
+        .filter(|(id, _)| simulation.has_attribute(*id, Attribute::Stop))
+        .map(|(_, object)| object.pos)
+        .collect();
+
+    assert_eq!(stops, vec![GridPos::new(0, 0)]);
+}
+
+#[test]
+fn sink_needs_something_else_on_its_tile() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you", "and", "sink"], 0, 3)
+        .rule(&["rock", "is", "push", "and", "sink"], 0, 2)
+        .object("bevy", 0, 0)
+        .object("rock", 4, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(4, 0)]);
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(1, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(4, 0)]);
+}
+
+#[test]
+fn sunk_objects_leave_what_they_have_behind() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["water", "is", "sink"], 0, 2)
+        .rule(&["bevy", "has", "rock", "and", "flag"], 0, 1)
+        .object("bevy", 5, 0)
+        .object("water", 6, 0);
+
+    let mut simulation = level.build();
+    let before = simulation.snapshot();
+
+    simulation.step(BlockDirection::Right);
+
+    assert!(positions_of(&simulation, level.block("bevy")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(6, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(6, 0)]);
+
+    assert!(simulation.undo());
+    assert_eq!(simulation.snapshot(), before);
+}
+
+#[test]
+fn make_fills_its_tile_once() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["bevy", "make", "rock"], 0, 2)
+        .object("bevy", 5, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::None);
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(5, 0)]);
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(5, 0), GridPos::new(6, 0)]);
+
+    assert!(simulation.undo());
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(5, 0)]);
+
+    // the second wait didn't make anything, so there is no turn of it to undo.
+    assert!(simulation.undo());
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+    assert!(!simulation.undo());
+}
+
+#[test]
+fn made_objects_get_their_rules_straight_away() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "make", "rock"], 0, 3)
+        .rule(&["rock", "is", "stop"], 0, 2)
+        .object("bevy", 5, 0);
+
+    let mut simulation = level.build();
+    simulation.step(BlockDirection::None);
+
+    assert!(block_has(&simulation, level.block("rock"), Attribute::Stop));
+}
+
+#[test]
+fn made_objects_transform_the_turn_they_are_made() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "make", "rock"], 0, 3)
+        .rule(&["rock", "is", "flag"], 0, 2)
+        .object("bevy", 5, 0);
+
+    let mut simulation = level.build();
+    simulation.step(BlockDirection::None);
+
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(5, 0)]);
+}
+
+fn words_at(simulation: &Simulation, pos: GridPos) -> Vec<TextBlock> {
+    simulation.occupants(pos)
+        .filter(|(_, object)| object.block == Block::Text)
+        .filter_map(|(_, object)| object.text)
+        .collect()
+}
+
+#[test]
+fn text_is_a_noun() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["text", "is", "win", "and", "not", "push"], 0, 3)
+        .object("rock", 7, 0);
+
+    let simulation = level.build();
+
+    assert!(block_has(&simulation, Block::Text, Attribute::Win));
+    assert!(!block_has(&simulation, Block::Text, Attribute::Push));
+}
+
+#[test]
+fn objects_turn_into_their_word() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "is", "text"], 0, 3)
+        .object("rock", 5, 0)
+        .object("level 01", 6, 0);
+
+    let mut simulation = level.build();
+    simulation.step(BlockDirection::None);
+
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+    assert_eq!(words_at(&simulation, GridPos::new(5, 0)), vec![level.word("rock")]);
+
+    // level tiles have no word to turn into.
+    let level = TestLevel::new(8, 4)
+        .rule(&["not", "rock", "is", "text"], 0, 3)
+        .object("level 01", 6, 0);
+
+    let mut simulation = level.build();
+    simulation.step(BlockDirection::None);
+
+    assert_eq!(positions_of(&simulation, level.block("level 01")), vec![GridPos::new(6, 0)]);
+}
+
+#[test]
+fn text_turns_into_objects() {
+    // pushing IS up finishes TEXT IS ROCK
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 3, 0)
+        .text("text", 0, 3)
+        .text("is", 1, 2)
+        .text("rock", 2, 3)
+        .object("bevy", 1, 1);
+
+    let mut simulation = level.build();
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+
+    simulation.step(BlockDirection::Up);
+
+    assert_eq!(positions_of(&simulation, level.block("rock")).len(), 6);
+    assert!(simulation.objects().all(|(_, object)| object.text.is_none()));
+
+    assert!(simulation.undo());
+    assert_eq!(words_at(&simulation, GridPos::new(1, 2)), vec![level.word("is")]);
+    assert_eq!(words_at(&simulation, GridPos::new(2, 3)), vec![level.word("rock")]);
+}
+
+#[test]
+fn words_made_from_objects_form_rules() {
+    // the rock becomes the word ROCK, finishing ROCK IS WIN
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "is", "text"], 0, 3)
+        .rule(&["is", "win"], 5, 2)
+        .object("rock", 4, 2);
+
+    let mut simulation = level.build();
+    simulation.step(BlockDirection::None);
+
+    assert_eq!(simulation.rules().len(), 2);
+}
+
+#[test]
+fn has_text_leaves_the_word_behind() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["water", "is", "sink"], 0, 2)
+        .rule(&["bevy", "has", "text"], 0, 1)
+        .object("bevy", 5, 0)
+        .object("water", 6, 0);
+
+    let mut simulation = level.build();
+    simulation.step(BlockDirection::Right);
+
+    assert_eq!(words_at(&simulation, GridPos::new(6, 0)), vec![level.word("bevy")]);
+}
+
+fn facing_of(simulation: &Simulation, block: Block) -> Vec<Facing> {
+    simulation.objects()
+        .filter(|(_, object)| object.block == block)
+        .map(|(_, object)| object.facing)
+        .collect()
+}
+
+#[test]
+fn move_walks_every_turn() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "is", "move"], 0, 3)
+        .object("rock", 2, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::None);
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(4, 0)]);
+}
+
+#[test]
+fn move_turns_around_when_blocked() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "is", "move"], 0, 3)
+        .rule(&["wall", "is", "stop"], 0, 2)
+        .object("rock", 5, 0)
+        .object("wall", 6, 0);
+
+    let mut simulation = level.build();
+
+    // walks back the other way within the same turn.
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(4, 0)]);
+    assert_eq!(facing_of(&simulation, level.block("rock")), vec![Facing::Left]);
+
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn move_stays_put_when_boxed_in() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "is", "move"], 0, 3)
+        .rule(&["wall", "is", "stop"], 0, 2)
+        .object("wall", 4, 0)
+        .object("rock", 5, 0)
+        .object("wall", 6, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(5, 0)]);
+    assert_eq!(facing_of(&simulation, level.block("rock")), vec![Facing::Left]);
+}
+
+#[test]
+fn move_pushes_like_you() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "is", "move"], 0, 3)
+        .rule(&["flag", "is", "push"], 0, 2)
+        .object("rock", 1, 0)
+        .object("flag", 2, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(2, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn move_walks_after_you() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you", "and", "push"], 0, 3)
+        .rule(&["rock", "is", "move"], 0, 2)
+        .object("rock", 2, 0)
+        .object("bevy", 3, 0);
+
+    let mut simulation = level.build();
+
+    // bevy is already out of the way by the time the rock walks.
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(4, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn you_turns_the_way_it_moves() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .object("bevy", 2, 0);
+
+    let mut simulation = level.build();
+    assert_eq!(facing_of(&simulation, level.block("bevy")), vec![Facing::Right]);
+
+    simulation.step(BlockDirection::Up);
+    assert_eq!(facing_of(&simulation, level.block("bevy")), vec![Facing::Up]);
+
+    simulation.step(BlockDirection::None);
+    assert_eq!(facing_of(&simulation, level.block("bevy")), vec![Facing::Up]);
+}
+
+#[test]
+fn pull_follows_what_moves_away() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "pull"], 0, 2)
+        .object("rock", 2, 0)
+        .object("bevy", 3, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(4, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)]);
+
+    // walking onto it or past its side leaves it where it is.
+    simulation.step(BlockDirection::Up);
+    simulation.step(BlockDirection::Left);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn pull_chains_along() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "and", "flag", "is", "pull"], 0, 2)
+        .object("flag", 1, 0)
+        .object("rock", 2, 0)
+        .object("bevy", 3, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(4, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(2, 0)]);
+}
+
+#[test]
+fn push_and_pull_move_together() {
+    let level = TestLevel::new(6, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "pull"], 0, 2)
+        .rule(&["flag", "is", "push"], 0, 1)
+        .object("rock", 2, 0)
+        .object("bevy", 3, 0)
+        .object("flag", 4, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(4, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(5, 0)]);
+
+    // the flag is against the edge, so nothing moves.
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(4, 0)]);
+}
+
+#[test]
+fn stop_blocks_what_is_pulled() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "and", "flag", "is", "pull"], 0, 2)
+        .rule(&["wall", "is", "stop"], 0, 1)
+        .object("flag", 1, 0)
+        .object("rock", 2, 0)
+        .object("wall", 3, 0)
+        .object("bevy", 3, 0);
+
+    let mut simulation = level.build();
+
+    // bevy gets off the wall, but the rock can't follow onto it and so the flag stays too.
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(4, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(2, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(1, 0)]);
+}
+
+#[test]
+fn blocked_moves_pull_nothing() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "pull"], 0, 2)
+        .rule(&["wall", "is", "stop"], 0, 1)
+        .object("rock", 2, 0)
+        .object("bevy", 3, 0)
+        .object("wall", 4, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(3, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(2, 0)]);
+}
+
+#[test]
+fn pulled_objects_only_move_once() {
+    // whichever of the two was spawned first, the rock is pulled or moves by itself, never both.
+    for order in [["bevy", "rock"], ["rock", "bevy"]] {
+        let mut level = TestLevel::new(8, 4)
+            .rule(&["bevy", "is", "you"], 0, 3)
+            .rule(&["rock", "is", "you", "and", "pull"], 0, 2);
+
+        for name in order {
+            level = level.object(name, if name == "rock" { 2 } else { 3 }, 0);
+        }
+
+        let mut simulation = level.build();
+
+        simulation.step(BlockDirection::Right);
+        assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)], "{:?}", order);
+        assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(4, 0)], "{:?}", order);
+    }
+}
+
+#[test]
+fn defeat_destroys_you() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "defeat"], 0, 2)
+        .rule(&["flag", "is", "push"], 0, 1)
+        .object("bevy", 2, 0)
+        .object("flag", 3, 0)
+        .object("rock", 4, 0);
+
+    let mut simulation = level.build();
+
+    // what isn't YOU is safe on it.
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(4, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(4, 0)]);
+
+    simulation.step(BlockDirection::Right);
+    assert!(positions_of(&simulation, level.block("bevy")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(4, 0)]);
+    assert_eq!(simulation.objects().count(), 11);
+}
+
+#[test]
+fn hot_destroys_melt() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you", "and", "melt"], 0, 3)
+        .rule(&["rock", "is", "hot"], 0, 2)
+        .object("bevy", 2, 0)
+        .object("rock", 3, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert!(positions_of(&simulation, level.block("bevy")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn open_and_shut_destroy_each_other_in_pairs() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you", "and", "open"], 0, 3)
+        .rule(&["wall", "is", "shut"], 0, 2)
+        .object("bevy", 2, 0)
+        .object("wall", 3, 0)
+        .object("wall", 3, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert!(positions_of(&simulation, level.block("bevy")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("wall")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn destroyed_objects_are_gone_until_undone() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "defeat"], 0, 2)
+        .object("bevy", 2, 0)
+        .object("rock", 3, 0);
+
+    let mut simulation = level.build();
+    let count = simulation.objects().count();
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(simulation.objects().count(), count - 1);
+    assert!(simulation.occupants(GridPos::new(3, 0)).all(|(_, object)| object.block == level.block("rock")));
+
+    assert!(simulation.undo());
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(2, 0)]);
+}
+
+#[test]
+fn float_keeps_objects_apart() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["water", "is", "sink"], 0, 2)
+        .rule(&["flag", "is", "win", "and", "float"], 0, 1)
+        .object("bevy", 2, 0)
+        .object("water", 3, 0)
+        .object("flag", 3, 0);
+
+    let mut simulation = level.build();
+
+    // bevy sinks in the water but never reaches the flag floating above it.
+    assert_eq!(simulation.step(BlockDirection::Right), TurnOutcome::Continue);
+    assert!(positions_of(&simulation, level.block("bevy")).is_empty());
+    assert!(positions_of(&simulation, level.block("water")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn floating_objects_interact_with_each_other() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you", "and", "float"], 0, 3)
+        .rule(&["rock", "is", "defeat"], 0, 2)
+        .rule(&["flag", "is", "win", "and", "float"], 0, 1)
+        .object("bevy", 2, 0)
+        .object("rock", 3, 0)
+        .object("flag", 4, 0);
+
+    let mut simulation = level.build();
+
+    assert_eq!(simulation.step(BlockDirection::Right), TurnOutcome::Continue);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(3, 0)]);
+
+    assert_eq!(simulation.step(BlockDirection::Right), TurnOutcome::Won);
+}
+
+#[test]
+fn shift_carries_what_is_on_it() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["path", "is", "shift"], 0, 3)
+        .rule(&["bevy", "is", "you"], 0, 2)
+        .object("path", 2, 0)
+        .object("rock", 2, 0)
+        .object("bevy", 2, 1);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("path")), vec![GridPos::new(2, 0)]);
+
+    // bevy steps on facing down and comes off facing the way the path does.
+    simulation.step(BlockDirection::Down);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(3, 0)]);
+    assert_eq!(facing_of(&simulation, level.block("bevy")), vec![Facing::Right]);
+}
+
+#[test]
+fn tele_sends_to_the_next_of_its_kind() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["path", "is", "tele"], 0, 3)
+        .rule(&["bevy", "is", "you"], 0, 2)
+        .object("bevy", 1, 0)
+        .object("path", 2, 0)
+        .object("path", 6, 0)
+        .object("rock", 6, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(6, 0)]);
+
+    // standing on one doesn't send it anywhere, nor does the rock that was already there.
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(6, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(6, 0)]);
+
+    simulation.step(BlockDirection::Left);
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(2, 0)]);
+}
+
+#[test]
+fn swap_trades_places() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "swap"], 0, 2)
+        .object("bevy", 2, 0)
+        .object("rock", 3, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(3, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(2, 0)]);
+}
+
+#[test]
+fn swap_movers_trade_places_with_anything() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you", "and", "swap"], 0, 3)
+        .rule(&["flag", "is", "push"], 0, 2)
+        .object("bevy", 2, 0)
+        .object("rock", 3, 0)
+        .object("flag", 3, 0);
+
+    let mut simulation = level.build();
+
+    // the flag is still pushed, only what stays behind is swapped.
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(3, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(4, 0)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(2, 0)]);
+}
+
+#[test]
+fn weak_breaks_when_sharing_a_tile() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "weak"], 0, 2)
+        .object("bevy", 2, 0)
+        .object("rock", 3, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn weak_breaks_when_pushed_into_something() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "push", "and", "weak"], 0, 2)
+        .rule(&["wall", "is", "stop"], 0, 1)
+        .object("bevy", 2, 0)
+        .object("rock", 3, 0)
+        .object("wall", 4, 0);
+
+    let mut simulation = level.build();
+
+    // the move is still blocked, the rock is just gone afterwards.
+    simulation.step(BlockDirection::Right);
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(2, 0)]);
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn weak_stop_breaks_when_walked_into() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["wall", "is", "stop", "and", "weak"], 0, 2)
+        .object("bevy", 2, 0)
+        .object("wall", 3, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert!(positions_of(&simulation, level.block("wall")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(2, 0)]);
+}
+
+#[test]
+fn more_grows_into_free_tiles() {
+    let level = TestLevel::new(5, 6)
+        .rule(&["rock", "is", "more"], 0, 5)
+        .rule(&["wall", "is", "stop"], 0, 4)
+        .object("rock", 1, 1)
+        .object("wall", 2, 1)
+        .object("flag", 1, 2);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::None);
+
+    let mut rocks = positions_of(&simulation, level.block("rock"));
+    rocks.sort_by_key(|pos| (pos.y, pos.x));
+
+    assert_eq!(rocks, vec![GridPos::new(1, 0), GridPos::new(0, 1), GridPos::new(1, 1), GridPos::new(1, 2)]);
+}
+
+#[test]
+fn more_stops_at_the_limit() {
+    let level = TestLevel::new(60, 60)
+        .rule(&["rock", "is", "more"], 0, 59)
+        .object("rock", 30, 30);
+
+    let mut simulation = level.build();
+
+    for _ in 0..40 {
+        simulation.step(BlockDirection::None);
+    }
+
+    assert_eq!(simulation.objects().count(), MORE_LIMIT);
+}
+
+#[test]
+fn more_level_stops_flooding_once_its_rule_is_broken() {
+    let registry = registry();
+    let level_json = serde_json::from_str(include_str!("../assets/maps/level5.json")).unwrap();
+    let level = bevy_is_you::level_data::create_level_data(level_json).unwrap();
+    let water = Block::Object(registry.noun("water").unwrap());
+
+    let mut simulation = Simulation::from_level(&level, &registry);
+    assert!(block_has(&simulation, water, Attribute::More));
+
+    for direction in [BlockDirection::Right, BlockDirection::Right, BlockDirection::Up, BlockDirection::Up, BlockDirection::Up, BlockDirection::Left] {
+        simulation.step(direction);
+    }
+
+    let flooded = positions_of(&simulation, water).len();
+    assert!(flooded > 1);
+
+    // pushes MORE out of WATER IS MORE.
+    simulation.step(BlockDirection::Up);
+    assert!(!block_has(&simulation, water, Attribute::More));
+
+    let flooded = positions_of(&simulation, water).len();
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, water).len(), flooded);
+}
+
+#[test]
+fn direction_words_turn_objects() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "is", "up", "and", "move"], 0, 3)
+        .object("rock", 2, 0);
+
+    let mut simulation = level.build();
+    assert_eq!(facing_of(&simulation, level.block("rock")), vec![Facing::Up]);
+
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(2, 1)]);
+}
+
+#[test]
+fn direction_words_win_over_moving() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you", "and", "left"], 0, 3)
+        .object("bevy", 2, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(3, 0)]);
+    assert_eq!(facing_of(&simulation, level.block("bevy")), vec![Facing::Left]);
+}
+
+#[test]
+fn fall_drops_until_blocked() {
+    let level = TestLevel::new(8, 8)
+        .rule(&["rock", "and", "flag", "is", "fall"], 0, 7)
+        .rule(&["wall", "is", "stop"], 0, 6)
+        .rule(&["flag", "is", "push"], 0, 5)
+        .object("rock", 3, 4)
+        .object("flag", 3, 3)
+        .object("wall", 3, 1)
+        .object("tree", 5, 4);
+
+    let mut simulation = level.build();
+
+    // the rock lands on the flag once the flag has landed on the wall, the tree doesn't fall.
+    simulation.step(BlockDirection::None);
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(3, 2)]);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 3)]);
+    assert_eq!(positions_of(&simulation, level.block("tree")), vec![GridPos::new(5, 4)]);
+}
+
+#[test]
+fn fall_comes_after_you() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you", "and", "fall"], 0, 3)
+        .object("bevy", 2, 2);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(3, 0)]);
+
+    simulation.step(BlockDirection::Up);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn colors_stay_on_objects_until_their_rule_goes() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "red"], 1, 1)
+        .object("bevy", 0, 0)
+        .object("rock", 6, 0);
+
+    let mut simulation = level.build();
+    let rock = |simulation: &Simulation| *simulation.objects().find(|(_, object)| object.block == level.block("rock")).unwrap().1;
+
+    assert_eq!(rock(&simulation).tint, Some(Tint::Red));
+
+    // pushes RED out of the rule from below.
+    simulation.step(BlockDirection::Right);
+    simulation.step(BlockDirection::Right);
+    simulation.step(BlockDirection::Right);
+    simulation.step(BlockDirection::Up);
+    assert_eq!(rock(&simulation).tint, None);
+
+    simulation.undo();
+    assert_eq!(rock(&simulation).tint, Some(Tint::Red));
+}
+
+#[test]
+fn adjectives_work_as_conditions() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["red", "rock", "is", "stop"], 0, 2)
+        .rule(&["rock", "is", "red"], 0, 1)
+        .object("bevy", 4, 0)
+        .object("rock", 5, 0);
+
+    let mut simulation = level.build();
+    assert!(block_has(&simulation, level.block("rock"), Attribute::Stop));
+
+    simulation.step(BlockDirection::Right);
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(4, 0)]);
+}
+
+#[test]
+fn hide_hides_objects() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["rock", "is", "hide"], 0, 3)
+        .object("rock", 5, 0);
+
+    let simulation = level.build();
+
+    assert!(simulation.objects().find(|(_, object)| object.block == level.block("rock")).unwrap().1.hidden);
+}
+
+#[test]
+fn noun_is_itself_locks_it() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["wall", "is", "rock", "and", "flag"], 0, 3)
+        .rule(&["wall", "is", "wall"], 0, 2)
+        .object("wall", 6, 0);
+
+    let mut simulation = level.build();
+    simulation.step(BlockDirection::None);
+
+    assert_eq!(positions_of(&simulation, level.block("wall")), vec![GridPos::new(6, 0)]);
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+    assert!(positions_of(&simulation, level.block("flag")).is_empty());
+}
+
+#[test]
+fn several_nouns_split_an_object() {
+    // pushing IS up finishes WALL IS ROCK AND FLAG AND TEXT
+    let level = TestLevel::new(10, 4)
+        .text("wall", 0, 3)
+        .text("is", 1, 2)
+        .rule(&["rock", "and", "flag", "and", "text"], 2, 3)
+        .rule(&["bevy", "is", "you"], 0, 0)
+        .object("bevy", 1, 1)
+        .object("wall", 6, 0)
+        .object("wall", 7, 1);
+
+    let mut simulation = level.build();
+    let count = simulation.objects().count();
+
+    simulation.step(BlockDirection::Up);
+
+    assert!(positions_of(&simulation, level.block("wall")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(6, 0), GridPos::new(7, 1)]);
+    assert_eq!(positions_of(&simulation, level.block("flag")), vec![GridPos::new(6, 0), GridPos::new(7, 1)]);
+    assert_eq!(words_at(&simulation, GridPos::new(6, 0)), vec![level.word("wall")]);
+    assert_eq!(simulation.objects().count(), count + 4);
+
+    // the split only happens once, rocks and flags aren't walls anymore.
+    simulation.step(BlockDirection::Right);
+    assert_eq!(simulation.objects().count(), count + 4);
+
+    assert!(simulation.undo());
+    assert!(simulation.undo());
+    assert_eq!(simulation.objects().count(), count);
+    assert_eq!(positions_of(&simulation, level.block("wall")).len(), 2);
+}
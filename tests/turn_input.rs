@@ -0,0 +1,73 @@
+use bevy::prelude::KeyCode;
+use bevy_is_you::{logic_types::BlockDirection, turn_input::*};
+
+const RIGHT: TurnCommand = TurnCommand::Move(BlockDirection::Right);
+
+#[test]
+fn taps_during_an_animation_are_buffered() {
+    let settings = InputSettings::default();
+    let mut turn_input = TurnInput::default();
+
+    turn_input.press(KeyCode::D, RIGHT, &settings);
+    turn_input.release(KeyCode::D);
+    turn_input.press(KeyCode::T, TurnCommand::Undo, &settings);
+    turn_input.release(KeyCode::T);
+
+    assert_eq!(turn_input.next_turn(), Some(RIGHT));
+    assert_eq!(turn_input.next_turn(), Some(TurnCommand::Undo));
+    assert_eq!(turn_input.next_turn(), None);
+}
+
+#[test]
+fn held_keys_repeat_after_the_initial_delay() {
+    let settings = InputSettings {
+        initial_delay: 0.3,
+        repeat_rate: 0.1,
+        ..Default::default()
+    };
+
+    let mut turn_input = TurnInput::default();
+
+    turn_input.press(KeyCode::D, RIGHT, &settings);
+    assert_eq!(turn_input.next_turn(), Some(RIGHT));
+
+    turn_input.tick(0.25, &settings);
+    assert_eq!(turn_input.next_turn(), None);
+
+    turn_input.tick(0.1, &settings);
+    assert_eq!(turn_input.next_turn(), Some(RIGHT));
+
+    turn_input.tick(0.1, &settings);
+    assert_eq!(turn_input.next_turn(), Some(RIGHT));
+
+    turn_input.release(KeyCode::D);
+    turn_input.tick(1.0, &settings);
+    assert_eq!(turn_input.next_turn(), None);
+}
+
+#[test]
+fn repeats_do_not_pile_up() {
+    let settings = InputSettings::default();
+    let mut turn_input = TurnInput::default();
+
+    turn_input.press(KeyCode::D, RIGHT, &settings);
+
+    for _ in 0..100 {
+        turn_input.tick(settings.repeat_rate, &settings);
+    }
+
+    assert_eq!(turn_input.next_turn(), Some(RIGHT));
+    assert_eq!(turn_input.next_turn(), None);
+}
+
+#[test]
+fn restart_does_not_repeat() {
+    let settings = InputSettings::default();
+    let mut turn_input = TurnInput::default();
+
+    turn_input.press(KeyCode::Back, TurnCommand::Restart, &settings);
+    assert_eq!(turn_input.next_turn(), Some(TurnCommand::Restart));
+
+    turn_input.tick(10.0, &settings);
+    assert_eq!(turn_input.next_turn(), None);
+}
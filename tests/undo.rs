@@ -0,0 +1,202 @@
+mod common;
+
+use bevy_is_you::{logic_types::*, simulation::*};
+use common::*;
+
+// plays `turns`, then checks undoing all of them gets back every snapshot along the way
+// and redoing them gets back to the end. Turns that change nothing aren't recorded and are skipped.
+fn assert_round_trip(simulation: &mut Simulation, turns: &[BlockDirection]) {
+    let mut snapshots = vec![simulation.snapshot()];
+
+    for direction in turns {
+        simulation.step(*direction);
+
+        if simulation.snapshot() != *snapshots.last().unwrap() {
+            snapshots.push(simulation.snapshot());
+        }
+    }
+
+    for snapshot in snapshots.iter().rev().skip(1) {
+        assert!(simulation.undo());
+        assert_eq!(simulation.snapshot(), *snapshot);
+    }
+
+    for snapshot in snapshots.iter().skip(1) {
+        assert!(simulation.redo());
+        assert_eq!(simulation.snapshot(), *snapshot);
+    }
+
+    assert!(!simulation.redo());
+}
+
+#[test]
+fn undo_and_redo_moves() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "push"], 0, 2)
+        .object("bevy", 0, 0)
+        .object("rock", 1, 0);
+
+    let mut simulation = level.build();
+    assert_round_trip(&mut simulation, &[BlockDirection::Right, BlockDirection::Right, BlockDirection::Up, BlockDirection::Left]);
+
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(3, 0)]);
+}
+
+#[test]
+fn undo_restores_sunk_objects() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["water", "is", "sink"], 0, 2)
+        .object("bevy", 0, 0)
+        .object("water", 1, 0);
+
+    let mut simulation = level.build();
+    let before = simulation.snapshot();
+
+    simulation.step(BlockDirection::Right);
+    assert!(positions_of(&simulation, level.block("bevy")).is_empty());
+    assert!(positions_of(&simulation, level.block("water")).is_empty());
+
+    assert!(simulation.undo());
+    assert_eq!(simulation.snapshot(), before);
+    assert!(block_has(&simulation, level.block("bevy"), Attribute::You));
+
+    assert_round_trip(&mut simulation, &[BlockDirection::Right, BlockDirection::Left]);
+}
+
+#[test]
+fn undo_reverts_transforms_and_rules() {
+    // pushing WALL into place forms ROCK IS WALL
+    let level = TestLevel::new(8, 5)
+        .rule(&["bevy", "is", "you"], 0, 4)
+        .text("rock", 0, 2)
+        .text("is", 1, 2)
+        .text("wall", 2, 1)
+        .object("bevy", 2, 0)
+        .object("rock", 5, 0);
+
+    let mut simulation = level.build();
+    let before = simulation.snapshot();
+
+    simulation.step(BlockDirection::Up);
+    assert!(positions_of(&simulation, level.block("rock")).is_empty());
+    assert_eq!(positions_of(&simulation, level.block("wall")), vec![GridPos::new(5, 0)]);
+
+    assert!(simulation.undo());
+    assert_eq!(simulation.snapshot(), before);
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(5, 0)]);
+
+    assert_round_trip(&mut simulation, &[BlockDirection::Up, BlockDirection::Right, BlockDirection::Down]);
+}
+
+#[test]
+fn undo_after_level_select() {
+    let level = TestLevel::new(4, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .object("bevy", 0, 0)
+        .object("level 02", 1, 0);
+
+    let mut simulation = level.build();
+
+    assert_eq!(simulation.step(BlockDirection::Right), TurnOutcome::LevelSelect(2));
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(1, 0)]);
+
+    assert!(simulation.undo());
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(0, 0)]);
+    assert_eq!(simulation.step(BlockDirection::Up), TurnOutcome::Continue);
+
+    assert_round_trip(&mut simulation, &[BlockDirection::Right, BlockDirection::Right, BlockDirection::Left]);
+}
+
+#[test]
+fn new_turn_discards_undone_turns() {
+    let level = TestLevel::new(4, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .object("bevy", 0, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+    assert!(simulation.undo());
+
+    simulation.step(BlockDirection::Up);
+    assert!(!simulation.redo());
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(0, 1)]);
+}
+
+#[test]
+fn restart_can_be_undone() {
+    let level = TestLevel::new(8, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .rule(&["rock", "is", "push"], 0, 2)
+        .object("bevy", 0, 0)
+        .object("rock", 1, 0);
+
+    let mut simulation = level.build();
+    let start = simulation.snapshot();
+
+    simulation.step(BlockDirection::Right);
+    simulation.step(BlockDirection::Right);
+    let before_restart = simulation.snapshot();
+
+    assert!(simulation.restart());
+    assert_eq!(simulation.snapshot(), start);
+    assert!(!simulation.restart());
+
+    assert!(simulation.undo());
+    assert_eq!(simulation.snapshot(), before_restart);
+
+    assert!(simulation.redo());
+    assert_eq!(simulation.snapshot(), start);
+
+    assert!(simulation.undo());
+    assert!(simulation.undo());
+    assert_eq!(positions_of(&simulation, level.block("rock")), vec![GridPos::new(2, 0)]);
+}
+
+#[test]
+fn undo_and_redo_shift_tele_and_swap() {
+    let level = TestLevel::new(8, 5)
+        .rule(&["bevy", "is", "you"], 0, 4)
+        .rule(&["path", "is", "tele"], 0, 3)
+        .rule(&["water", "is", "shift"], 0, 2)
+        .rule(&["rock", "is", "swap"], 4, 2)
+        .object("bevy", 0, 0)
+        .object("water", 1, 0)
+        .object("path", 2, 0)
+        .object("path", 6, 1)
+        .object("rock", 5, 1);
+
+    let mut simulation = level.build();
+    assert_round_trip(&mut simulation, &[BlockDirection::Right, BlockDirection::None, BlockDirection::Left, BlockDirection::Down]);
+
+    // the replay ends up exactly where the first play did.
+    let end = simulation.snapshot();
+    simulation.restart();
+
+    for direction in [BlockDirection::Right, BlockDirection::None, BlockDirection::Left, BlockDirection::Down] {
+        simulation.step(direction);
+    }
+
+    assert_eq!(simulation.snapshot(), end);
+}
+
+#[test]
+fn turn_that_changes_nothing_is_not_recorded() {
+    let level = TestLevel::new(4, 4)
+        .rule(&["bevy", "is", "you"], 0, 3)
+        .object("bevy", 2, 0);
+
+    let mut simulation = level.build();
+
+    simulation.step(BlockDirection::Right);
+
+    // bevy is already facing the edge of the level it walks into.
+    simulation.step(BlockDirection::Right);
+    simulation.step(BlockDirection::None);
+
+    assert!(simulation.undo());
+    assert_eq!(positions_of(&simulation, level.block("bevy")), vec![GridPos::new(2, 0)]);
+    assert!(!simulation.undo());
+}